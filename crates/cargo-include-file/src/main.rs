@@ -0,0 +1,613 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+mod pins;
+
+use clap::{Parser, Subcommand};
+use include_file_core::{parse_range, select, Block, Duplicates, Format, Selector};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    IncludeFile(Args),
+}
+
+/// List, show, pin, and lint code blocks used by include-file macros.
+#[derive(Debug, clap::Args)]
+#[command(version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List the names, languages, and line ranges of code blocks in a file.
+    List {
+        /// Path to an AsciiDoc, Markdown, Org, or Textile file.
+        file: PathBuf,
+
+        /// Only list code blocks in this language e.g., `rust`.
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Print code blocks as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the content of a Rust code block exactly as the macros include it.
+    Show {
+        /// Path to an AsciiDoc, Markdown, Org, or Textile file.
+        file: PathBuf,
+
+        #[command(flatten)]
+        select: SelectArgs,
+    },
+
+    /// Print the SHA-256 hash of a Rust code block to pin with `sha256 = ".."`, or of all named Rust code blocks.
+    Pin {
+        /// Path to an AsciiDoc, Markdown, Org, or Textile file.
+        file: PathBuf,
+
+        #[command(flatten)]
+        select: SelectArgs,
+
+        /// Rewrite changed `sha256 = ".."` hashes of macro calls including the file in these Rust source files.
+        #[arg(long, value_name = "SOURCE", num_args = 1..)]
+        write: Vec<PathBuf>,
+    },
+
+    /// Check files for duplicate names, unterminated code blocks, and unnamed Rust code blocks.
+    Lint {
+        /// Paths to AsciiDoc, Markdown, Org, or Textile files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Selects code blocks like the parameters of the macros.
+#[derive(Debug, clap::Args)]
+struct SelectArgs {
+    /// Name of the Rust code block.
+    name: Option<String>,
+
+    /// 1-based ordinal of the Rust code block, like `index = N`.
+    #[arg(long, conflicts_with_all = ["name", "lines"])]
+    index: Option<usize>,
+
+    /// Range of lines within a Rust code block, like `lines = "40..55"`.
+    #[arg(long, conflicts_with_all = ["name", "index"], value_parser = parse_range)]
+    lines: Option<Range<u32>>,
+
+    /// Select the "first", "last", or "all" code blocks with the name, like `duplicates = ".."`.
+    #[arg(long, requires = "name", value_parser = str::parse::<Duplicates>)]
+    duplicates: Option<Duplicates>,
+
+    /// Select code blocks in any language, like `include_markdown_data!`.
+    #[arg(long)]
+    data: bool,
+}
+
+impl SelectArgs {
+    /// Returns the selector passed, if any.
+    fn selector(&self) -> Option<Selector> {
+        match (&self.name, self.index, &self.lines) {
+            (Some(name), ..) => Some(Selector::Name(name.clone())),
+            (_, Some(index), _) => Some(Selector::Index(index)),
+            (.., Some(range)) => Some(Selector::Lines(range.clone())),
+            _ => None,
+        }
+    }
+
+    /// The language of code blocks to select, or `None` for any language.
+    fn lang(&self) -> Option<&'static str> {
+        (!self.data).then_some("rust")
+    }
+}
+
+fn main() -> ExitCode {
+    let Cargo::IncludeFile(args) = Cargo::parse();
+    let mut stdout = io::stdout().lock();
+    let result = match args.command {
+        Command::List { file, lang, json } => list(&file, lang.as_deref(), json, &mut stdout),
+        Command::Show { file, select } => show(&file, &select, &mut stdout),
+        Command::Pin {
+            file,
+            select,
+            write,
+        } if !write.is_empty() => write_pins(&file, select.name.as_deref(), &write, &mut stdout),
+        Command::Pin { file, select, .. } => pin(&file, &select, &mut stdout),
+        Command::Lint { files } => lint(&files, &mut stdout),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn collect(path: &Path, lang: Option<&str>) -> io::Result<Vec<Block>> {
+    let format = Format::from_path(path)?;
+    let file = fs::File::open(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+    format.collect(file, lang)
+}
+
+fn list(path: &Path, lang: Option<&str>, json: bool, w: &mut impl Write) -> io::Result<bool> {
+    let blocks = collect(path, lang)?;
+    if json {
+        let blocks: Vec<_> = blocks
+            .iter()
+            .map(|block| {
+                json!({
+                    "lang": block.lang,
+                    "name": block.name(),
+                    "names": block.names,
+                    "start_line": block.start_line,
+                    "end_line": block.end_line(),
+                    "untested": block.untested,
+                    "unterminated": block.unterminated,
+                    "tangle": block.tangle,
+                })
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut *w, &blocks)?;
+        writeln!(w)?;
+        return Ok(true);
+    }
+
+    let rows: Vec<_> = blocks
+        .iter()
+        .map(|block| {
+            (
+                format!("{}-{}", block.start_line, block.end_line()),
+                block.lang.as_deref().unwrap_or("-"),
+                block.name().unwrap_or("-"),
+            )
+        })
+        .collect();
+    let lines_width = rows.iter().map(|row| row.0.len()).fold(5, usize::max);
+    let lang_width = rows.iter().map(|row| row.1.len()).fold(4, usize::max);
+    writeln!(w, "{:lines_width$}  {:lang_width$}  NAME", "LINES", "LANG")?;
+    for (lines, lang, name) in rows {
+        writeln!(w, "{lines:lines_width$}  {lang:lang_width$}  {name}")?;
+    }
+    Ok(true)
+}
+
+/// Returns the content selected by `args` from the file at `path` exactly as the macros include it,
+/// failing with the same errors as the macros e.g., for duplicate names or unterminated code blocks.
+fn content(path: &Path, args: &SelectArgs, selector: &Selector) -> io::Result<String> {
+    let blocks = collect(path, args.lang())?;
+    let selected = select(
+        selector,
+        args.duplicates,
+        &blocks,
+        &path.display().to_string(),
+    )?;
+    Ok(join(&selected))
+}
+
+/// Joins the content of code blocks returned by [`select`] as the macros include them.
+fn join(selected: &[(u32, String)]) -> String {
+    let content: Vec<&str> = selected
+        .iter()
+        .map(|(_, content)| content.as_str())
+        .collect();
+    content.join("\n")
+}
+
+fn show(path: &Path, args: &SelectArgs, w: &mut impl Write) -> io::Result<bool> {
+    let selector = args.selector().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "a name, --index, or --lines is required",
+        )
+    })?;
+    writeln!(w, "{}", content(path, args, &selector)?)?;
+    Ok(true)
+}
+
+fn pin(path: &Path, args: &SelectArgs, w: &mut impl Write) -> io::Result<bool> {
+    if let Some(selector) = args.selector() {
+        let content = content(path, args, &selector)?;
+        writeln!(w, "{}", include_file_core::sha256(&content))?;
+        return Ok(true);
+    }
+
+    let blocks = collect(path, args.lang())?;
+    let rows: Vec<_> = blocks
+        .iter()
+        .filter_map(|block| Some((block.name()?, sha256(block))))
+        .collect();
+    let name_width = rows.iter().map(|row| row.0.len()).fold(4, usize::max);
+    writeln!(w, "{:name_width$}  SHA256", "NAME")?;
+    for (name, hash) in rows {
+        writeln!(w, "{name:name_width$}  {hash}")?;
+    }
+    Ok(true)
+}
+
+/// Returns the SHA-256 hash of `block` as lowercase hexadecimal, as the macros hash it.
+fn sha256(block: &Block) -> String {
+    include_file_core::sha256(&block.lines.join("\n"))
+}
+
+/// Rewrites the `sha256` hashes in `sources` of macro calls that include code blocks from `path`,
+/// or only the code block `name`, printing the location of each hash changed.
+///
+/// Macro calls are matched by their `path` as passed, so pass the file as the macros do relative to the crate.
+/// Each hash is of the content the macro includes, selected by its `name`, `index`, `lines`, and `duplicates` parameters.
+fn write_pins(
+    path: &Path,
+    name: Option<&str>,
+    sources: &[PathBuf],
+    w: &mut impl Write,
+) -> io::Result<bool> {
+    let rust = collect(path, Some("rust"))?;
+    let data = collect(path, None)?;
+    let display_path = path.display().to_string();
+    for source in sources {
+        let mut content = fs::read_to_string(source)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", source.display())))?;
+        let mut changed = Vec::new();
+        for pin in pins::find(&content) {
+            if !Path::new(&pin.path).components().eq(path.components())
+                || name.is_some_and(|name| pin.name.as_deref() != Some(name))
+            {
+                continue;
+            }
+            let at = |err: io::Error| {
+                io::Error::new(
+                    err.kind(),
+                    format!("{}:{}: {err}", source.display(), pin.line),
+                )
+            };
+            let invalid = |err: String| at(io::Error::new(io::ErrorKind::InvalidInput, err));
+            let selector = match (&pin.name, pin.index, &pin.lines) {
+                (Some(name), ..) => Selector::Name(name.clone()),
+                (_, Some(index), _) => Selector::Index(index),
+                (.., Some(lines)) => Selector::Lines(parse_range(lines).map_err(invalid)?),
+                _ => continue,
+            };
+            let duplicates = pin
+                .duplicates
+                .as_deref()
+                .map(str::parse::<Duplicates>)
+                .transpose()
+                .map_err(invalid)?;
+            // Data macros include code blocks in any language.
+            let blocks = match pin.macro_name.ends_with("_data") {
+                true => &data,
+                false => &rust,
+            };
+            let selected = select(&selector, duplicates, blocks, &display_path).map_err(at)?;
+            let hash = include_file_core::sha256(&join(&selected));
+            if !content[pin.range.clone()].eq_ignore_ascii_case(&hash) {
+                changed.push((pin, selector, hash));
+            }
+        }
+
+        // Replace hashes from the end so earlier ranges remain valid.
+        for (pin, _, hash) in changed.iter().rev() {
+            content.replace_range(pin.range.clone(), hash);
+        }
+        if !changed.is_empty() {
+            fs::write(source, content)?;
+        }
+        for (pin, selector, hash) in changed {
+            let label = match pin.name {
+                Some(name) => name,
+                None => selector.to_string(),
+            };
+            writeln!(w, "{}:{}: {label}  {hash}", source.display(), pin.line)?;
+        }
+    }
+    Ok(true)
+}
+
+fn lint(paths: &[PathBuf], w: &mut impl Write) -> io::Result<bool> {
+    let mut ok = true;
+    for path in paths {
+        for (line, message) in problems(path)? {
+            writeln!(w, "{}:{line}: {message}", path.display())?;
+            ok = false;
+        }
+    }
+    Ok(ok)
+}
+
+/// Returns the line number and description of each problem found in `path`, sorted by line number.
+fn problems(path: &Path) -> io::Result<Vec<(u32, String)>> {
+    let mut problems = Vec::new();
+
+    // Any unterminated code block may hide the rest of the file.
+    for block in collect(path, None)? {
+        if block.unterminated {
+            let message = match block.name() {
+                Some(name) => format!("unterminated code block '{name}'"),
+                None => "unterminated code block".to_string(),
+            };
+            problems.push((block.open_line, message));
+        }
+    }
+
+    // Macros only select Rust code blocks, and fail on duplicate names by default.
+    let mut names: HashMap<&str, u32> = HashMap::new();
+    let blocks = collect(path, Some("rust"))?;
+    for block in &blocks {
+        let line = block.open_line;
+        match block.name() {
+            Some(name) => {
+                if let Some(first) = names.get(name) {
+                    problems.push((
+                        line,
+                        format!("duplicate name '{name}' first used on line {first}"),
+                    ));
+                } else {
+                    names.insert(name, line);
+                }
+            }
+            None if !block.untested => problems.push((line, "unnamed Rust code block".into())),
+            None => {}
+        }
+    }
+
+    problems.sort_by_key(|(line, _)| *line);
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, pin, problems, show, write_pins, SelectArgs};
+    use clap::Parser;
+    use std::{env, fs, path::PathBuf};
+
+    fn write(name: &str, content: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("cargo-include-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join(name);
+        fs::write(&path, content).expect("write temp file");
+        path
+    }
+
+    #[derive(Parser)]
+    struct Select {
+        #[command(flatten)]
+        select: SelectArgs,
+    }
+
+    fn select(args: &[&str]) -> SelectArgs {
+        Select::parse_from(std::iter::once("select").chain(args.iter().copied())).select
+    }
+
+    #[test]
+    fn list_table() {
+        let path = write(
+            "list.md",
+            "```rust example\nlet x = 1;\n```\n\n```sh\ncargo test\n```\n",
+        );
+        let mut out = Vec::new();
+        assert!(list(&path, None, false, &mut out).expect("list"));
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            "LINES  LANG  NAME\n2-2    rust  example\n6-6    sh    -\n"
+        );
+    }
+
+    #[test]
+    fn list_json() {
+        let path = write(
+            "list.org",
+            "#+NAME: example\n#+BEGIN_SRC rust\nlet x = 1;\n",
+        );
+        let mut out = Vec::new();
+        assert!(list(&path, Some("rust"), true, &mut out).expect("list"));
+        let actual: serde_json::Value = serde_json::from_slice(&out).expect("json");
+        assert_eq!(
+            actual,
+            serde_json::json!([{
+                "lang": "rust",
+                "name": "example",
+                "names": ["example"],
+                "start_line": 3,
+                "end_line": 3,
+                "untested": false,
+                "unterminated": true,
+                "tangle": null,
+            }])
+        );
+    }
+
+    #[test]
+    fn show_name() {
+        let path = write(
+            "show.adoc",
+            "[,rust,id=\"example\"]\n----\nlet x = 1;\nlet y = 2;\n----\n",
+        );
+        let mut out = Vec::new();
+        assert!(show(&path, &select(&["example"]), &mut out).expect("show"));
+        assert_eq!(out, b"let x = 1;\nlet y = 2;\n");
+
+        let mut out = Vec::new();
+        assert!(show(&path, &select(&["--lines", "4..5"]), &mut out).expect("show"));
+        assert_eq!(out, b"let y = 2;\n");
+    }
+
+    #[test]
+    fn show_like_macros() {
+        let path = write(
+            "show.md",
+            "```rust example\nlet x = 1;\n```\n\n```rust example\nlet y = 2;\n```\n\n```rust other\n",
+        );
+        let err = show(&path, &select(&["example"]), &mut Vec::new())
+            .expect_err("expected ambiguous name");
+        assert!(
+            err.to_string()
+                .starts_with("code fence 'example' is ambiguous; found in "),
+            "{err}"
+        );
+
+        let mut out = Vec::new();
+        assert!(show(
+            &path,
+            &select(&["example", "--duplicates", "last"]),
+            &mut out
+        )
+        .expect("show"));
+        assert_eq!(out, b"let y = 2;\n");
+
+        let mut out = Vec::new();
+        assert!(show(&path, &select(&["--index", "2"]), &mut out).expect("show"));
+        assert_eq!(out, b"let y = 2;\n");
+
+        let err = show(&path, &select(&["missing"]), &mut Vec::new())
+            .expect_err("expected unterminated code fence");
+        assert!(
+            err.to_string()
+                .starts_with("unterminated code fence 'other' opened at "),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn pin_hashes() {
+        let path = write(
+            "pin.md",
+            "```rust example\nlet x = 1;\n```\n\n```rust\nlet y = 2;\n```\n",
+        );
+        let hash = "aad7378dfeb851703927a534da47d0d02be7f54cf8c1751a37734d515811dafe";
+
+        let mut out = Vec::new();
+        assert!(pin(&path, &select(&["example"]), &mut out).expect("pin"));
+        assert_eq!(String::from_utf8(out).expect("utf8"), format!("{hash}\n"));
+
+        let mut out = Vec::new();
+        assert!(pin(&path, &select(&["--index", "1"]), &mut out).expect("pin"));
+        assert_eq!(String::from_utf8(out).expect("utf8"), format!("{hash}\n"));
+
+        let mut out = Vec::new();
+        assert!(pin(&path, &select(&[]), &mut out).expect("pin"));
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            format!("NAME     SHA256\nexample  {hash}\n")
+        );
+    }
+
+    #[test]
+    fn write_pins_changed() {
+        let path = write(
+            "write.md",
+            "```rust example\nlet x = 1;\n```\n\n```rust other\nlet y = 2;\n```\n",
+        );
+        let hash = "aad7378dfeb851703927a534da47d0d02be7f54cf8c1751a37734d515811dafe";
+        let old = "0".repeat(64);
+        let source = write(
+            "write.rs",
+            &format!(
+                "include_markdown!(r#\"{path}\"#, \"example\", sha256 = \"{old}\");\ninclude_markdown!(\"other.md\", \"example\", sha256 = \"{old}\");\n",
+                path = path.display(),
+            ),
+        );
+
+        let mut out = Vec::new();
+        assert!(write_pins(&path, None, std::slice::from_ref(&source), &mut out).expect("write"));
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            format!("{}:1: example  {hash}\n", source.display())
+        );
+        let content = fs::read_to_string(&source).expect("read source");
+        assert!(
+            content.contains(&format!("sha256 = \"{hash}\");\ninclude")),
+            "{content}"
+        );
+        assert!(
+            content.ends_with(&format!("sha256 = \"{old}\");\n")),
+            "{content}"
+        );
+
+        // Nothing is written when hashes match.
+        let mut out = Vec::new();
+        assert!(write_pins(&path, Some("example"), &[source], &mut out).expect("write"));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn write_pins_like_macros() {
+        let path = write(
+            "write-like-macros.md",
+            "```rust example\nlet x = 1;\n```\n\n```toml config\n[package]\n```\n\n```rust example\nlet y = 2;\nlet z = 3;\n```\n",
+        );
+        let hash = |content: &str| include_file_core::sha256(content);
+        let old = "0".repeat(64);
+        let source = write(
+            "write-like-macros.rs",
+            &format!(
+                r#"include_markdown!(r"{path}", "example", duplicates = "last", sha256 = "{old}");
+include_markdown_data!(r"{path}", "config", format = "toml", sha256 = "{old}");
+include_markdown!(r"{path}", index = 2, sha256 = "{old}");
+include_markdown!(r"{path}", lines = "11..12", sha256 = "{old}");
+"#,
+                path = path.display(),
+            ),
+        );
+
+        let mut out = Vec::new();
+        assert!(write_pins(&path, None, std::slice::from_ref(&source), &mut out).expect("write"));
+        let source_path = source.display();
+        assert_eq!(
+            String::from_utf8(out).expect("utf8"),
+            format!(
+                "{source_path}:1: example  {}\n{source_path}:2: config  {}\n{source_path}:3: code fence index 2  {}\n{source_path}:4: line range 11..12  {}\n",
+                hash("let y = 2;\nlet z = 3;"),
+                hash("[package]"),
+                hash("let y = 2;\nlet z = 3;"),
+                hash("let z = 3;"),
+            )
+        );
+
+        // Duplicate names are ambiguous without `duplicates`, as in the macros.
+        let source = write(
+            "write-ambiguous.rs",
+            &format!(
+                r#"include_markdown!(r"{path}", "example", sha256 = "{old}");"#,
+                path = path.display(),
+            ),
+        );
+        let err = write_pins(&path, None, std::slice::from_ref(&source), &mut Vec::new())
+            .expect_err("expected ambiguous name");
+        assert!(err.to_string().contains("is ambiguous"), "{err}");
+    }
+
+    #[test]
+    fn lint_problems() {
+        let path = write(
+            "lint.md",
+            "```rust example\n```\n\n```rust\n```\n\n```rust,untested\n```\n\n```rust example\n```\n\n```text\n",
+        );
+        assert_eq!(
+            problems(&path).expect("problems"),
+            vec![
+                (4, "unnamed Rust code block".to_string()),
+                (
+                    10,
+                    "duplicate name 'example' first used on line 1".to_string()
+                ),
+                (13, "unterminated code block".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use std::ops::Range;
+
+/// A `sha256 = ".."` parameter of a macro call in Rust source.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pin {
+    /// The name of the macro e.g., `include_markdown`.
+    pub macro_name: String,
+    /// The first string literal passed to the macro, which is the path of the file.
+    pub path: String,
+    /// The string literal passed after the path, which is the name of the code block.
+    pub name: Option<String>,
+    /// The `index = N` parameter, if any.
+    pub index: Option<usize>,
+    /// The `lines = ".."` parameter, if any.
+    pub lines: Option<String>,
+    /// The `duplicates = ".."` parameter, if any.
+    pub duplicates: Option<String>,
+    /// The 1-based line of the hash.
+    pub line: usize,
+    /// The byte range of the hash within the source, excluding quotes.
+    pub range: Range<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    /// The content of a string literal and its byte range within the source, excluding quotes.
+    Str(String, Range<usize>),
+    Punct(char),
+}
+
+/// Returns the `sha256 = ".."` parameters of macro calls like `include_markdown!("README.md", "example", sha256 = "..")` in `source`.
+pub fn find(source: &str) -> Vec<Pin> {
+    let tokens = tokens(source);
+    let mut pins = Vec::new();
+
+    // Each open bracket pushes the macro name and the index of its first token if it opens a macro call.
+    let mut stack: Vec<Option<(&str, usize)>> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => {
+                let macro_name = match tokens.get(i.wrapping_sub(2)..i) {
+                    Some([Token::Ident(name), Token::Punct('!')]) => Some((name.as_str(), i + 1)),
+                    _ => None,
+                };
+                stack.push(macro_name);
+            }
+            Token::Punct(')' | ']' | '}') => {
+                if let Some(Some((macro_name, start))) = stack.pop() {
+                    pins.extend(call(source, macro_name, &tokens[start..i]));
+                }
+            }
+            _ => {}
+        }
+    }
+    pins
+}
+
+/// Returns the `sha256` parameter of the call to `macro_name` with `args`, if any.
+fn call(source: &str, macro_name: &str, args: &[Token]) -> Option<Pin> {
+    let Some(Token::Str(path, _)) = args.first() else {
+        return None;
+    };
+    let name = match args.get(1..3) {
+        Some([Token::Punct(','), Token::Str(name, _)]) => Some(name.clone()),
+        _ => None,
+    };
+
+    // Only parameters outside any brackets within the call are considered.
+    let mut depth = 0usize;
+    let mut params = Vec::new();
+    for (i, token) in args.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth = depth.saturating_sub(1),
+            Token::Ident(ident) if depth == 0 => {
+                if let Some([Token::Punct('='), value]) = args.get(i + 1..i + 3) {
+                    params.push((ident.as_str(), value));
+                }
+            }
+            _ => {}
+        }
+    }
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| *value)
+    };
+    let string = |name: &str| match param(name) {
+        Some(Token::Str(value, _)) => Some(value.clone()),
+        _ => None,
+    };
+
+    let Some(Token::Str(_, range)) = param("sha256") else {
+        return None;
+    };
+    Some(Pin {
+        macro_name: macro_name.to_string(),
+        path: path.clone(),
+        name,
+        index: match param("index") {
+            Some(Token::Ident(index)) => index.parse().ok(),
+            _ => None,
+        },
+        lines: string("lines"),
+        duplicates: string("duplicates"),
+        line: source[..range.start].matches('\n').count() + 1,
+        range: range.clone(),
+    })
+}
+
+/// A minimal lexer that skips comments, and character and numeric literals, so brackets and strings are matched.
+fn tokens(source: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(source.len(), |(offset, _)| *offset);
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i].1 != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let mut nested = 0;
+            while i < chars.len() {
+                let next = chars.get(i + 1).map(|(_, c)| *c);
+                if chars[i].1 == '/' && next == Some('*') {
+                    nested += 1;
+                    i += 2;
+                } else if chars[i].1 == '*' && next == Some('/') {
+                    nested -= 1;
+                    i += 2;
+                    if nested == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == '"' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i].1 != '"' {
+                i += if chars[i].1 == '\\' { 2 } else { 1 };
+            }
+            let range = offset(start)..offset(i);
+            tokens.push(Token::Str(source[range.clone()].to_string(), range));
+            i += 1;
+        } else if c == 'r' && matches!(next, Some('"' | '#')) {
+            // A raw string like r#".."#, or an identifier like `r#type`.
+            let hashes = chars[i + 1..].iter().take_while(|(_, c)| *c == '#').count();
+            if chars.get(i + 1 + hashes).map(|(_, c)| *c) != Some('"') {
+                tokens.push(Token::Ident("r".into()));
+                i += 1;
+                continue;
+            }
+            let start = i + 2 + hashes;
+            i = start;
+            while i < chars.len()
+                && !(chars[i].1 == '"'
+                    && chars[i + 1..]
+                        .iter()
+                        .take(hashes)
+                        .filter(|(_, c)| *c == '#')
+                        .count()
+                        == hashes)
+            {
+                i += 1;
+            }
+            let range = offset(start)..offset(i);
+            tokens.push(Token::Str(source[range.clone()].to_string(), range));
+            i += 1 + hashes;
+        } else if c == '\'' {
+            // A character literal like 'a' or '\n', or a lifetime like 'a.
+            if next == Some('\\') {
+                i += 2;
+                while i < chars.len() && chars[i].1 != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            } else if chars.get(i + 2).map(|(_, c)| *c) == Some('\'') {
+                i += 3;
+            } else {
+                i += 1;
+            }
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(source[offset(start)..offset(i)].to_string()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, Pin};
+
+    #[test]
+    fn find_pins() {
+        let source = r##"// include_markdown!("README.md", "comment", sha256 = "0");
+fn test() {
+    let c = '(';
+    include_markdown!("README.md", "example", scope, sha256 = "aad7");
+    include_markdown!(r#"README.md"#, index = 1, sha256 = "bbd7", replace = [("a", "b")]);
+    println!("{}", include_markdown_data!("README.md", "config", format = "toml"));
+    include_markdown_data!("README.md", "config", format = "toml", duplicates = "last", sha256 = "ddd7");
+    include_org!("README.org", lines = "3..5", sha256 = "eed7");
+    include_asciidoc! {
+        "docs/*.adoc",
+        "other",
+        replace = [(regex("sha256 = "), "x")],
+        sha256 = "ccd7",
+    }
+}
+"##;
+        let pins = find(source);
+        let hash = |pin: &Pin| &source[pin.range.clone()];
+        assert_eq!(pins.len(), 5, "{pins:?}");
+        assert_eq!(pins[0].macro_name, "include_markdown");
+        assert_eq!(pins[0].path, "README.md");
+        assert_eq!(pins[0].name.as_deref(), Some("example"));
+        assert_eq!(pins[0].line, 4);
+        assert_eq!(hash(&pins[0]), "aad7");
+        assert_eq!(pins[1].path, "README.md");
+        assert_eq!(pins[1].name, None);
+        assert_eq!(pins[1].index, Some(1));
+        assert_eq!(hash(&pins[1]), "bbd7");
+        assert_eq!(pins[2].macro_name, "include_markdown_data");
+        assert_eq!(pins[2].name.as_deref(), Some("config"));
+        assert_eq!(pins[2].duplicates.as_deref(), Some("last"));
+        assert_eq!(hash(&pins[2]), "ddd7");
+        assert_eq!(pins[3].macro_name, "include_org");
+        assert_eq!(pins[3].lines.as_deref(), Some("3..5"));
+        assert_eq!(hash(&pins[3]), "eed7");
+        assert_eq!(pins[4].path, "docs/*.adoc");
+        assert_eq!(pins[4].name.as_deref(), Some("other"));
+        assert_eq!(pins[4].line, 13);
+        assert_eq!(hash(&pins[4]), "ccd7");
+    }
+}
@@ -0,0 +1,710 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{convert, Block};
+use std::io;
+
+/// Collects Rust source blocks.
+pub fn collect<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    collect_lang(iter, Some("rust"))
+}
+
+/// Collects source blocks in `lang`, or all source blocks if `lang` is `None`.
+pub fn collect_lang<R: io::Read>(
+    iter: io::Lines<io::BufReader<R>>,
+    lang: Option<&str>,
+) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut block: Option<Block> = None;
+    let mut delimiter_checked = false;
+    let mut use_delimiters = false;
+
+    for (line_idx, line) in iter.enumerate() {
+        let line = line?;
+        let line_num = (line_idx + 1) as u32;
+
+        let Some(current) = block.as_mut() else {
+            // Look for a source block attribute line like [source,rust] or [,rust]
+            let trimmed = line.trim();
+
+            // Check if this is a source block declaration
+            if let Some(source_lang) = parse_lang(trimmed)
+                .filter(|source_lang| lang.is_none_or(|lang| lang == *source_lang))
+            {
+                block = Some(Block {
+                    lang: Some(source_lang.to_string()),
+                    names: parse_id(trimmed).map(String::from).into_iter().collect(),
+                    untested: trimmed
+                        .trim_matches(|c| c == '[' || c == ']')
+                        .split(',')
+                        .any(|attr| attr.trim() == "untested"),
+                    tangle: parse_file(trimmed).map(String::from),
+                    open_line: line_num,
+                    start_line: line_num + 1, // adjusted below if a delimiter follows
+                    ..Default::default()
+                });
+                // Next line will determine if we use delimiters
+                delimiter_checked = false;
+                use_delimiters = false;
+            }
+            continue;
+        };
+
+        if !delimiter_checked {
+            // First line after the attribute line - check if it's a delimiter
+            delimiter_checked = true;
+            if line.trim() == "----" {
+                use_delimiters = true;
+                current.open_line = line_num;
+                current.start_line = line_num + 1; // content starts after ----
+                continue; // Don't collect the opening delimiter
+            }
+        }
+
+        if use_delimiters {
+            // We're using delimiters, collect until closing ----
+            if line.trim() == "----" {
+                // Found closing delimiter
+                blocks.extend(block.take());
+                continue;
+            }
+        } else if line.trim().is_empty() || line.trim() == "----" {
+            // Not using delimiters, collect until blank line or ---- (from outer block)
+            blocks.extend(block.take());
+            continue;
+        }
+        current.lines.push(line);
+    }
+
+    // An unterminated block continues to the end of the file.
+    blocks.extend(block.map(|block| Block {
+        unterminated: use_delimiters,
+        ..block
+    }));
+
+    Ok(blocks)
+}
+
+/// Converts AsciiDoc to Markdown, converting section titles, lists, links, emphasis, tables, and source and literal blocks.
+///
+/// Comments, attribute entries, block attributes, and anchors are removed.
+pub fn to_markdown<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<String>> {
+    let mut output = Vec::new();
+    let mut lang: Option<String> = None;
+    // The delimiter that closes the current block, or an empty string if it ends at a blank line.
+    let mut delimiter: Option<&str> = None;
+    // The cells of the current table, and the number of columns from its first row.
+    let mut table: Option<(Vec<String>, usize)> = None;
+
+    for line in iter {
+        let line = line?;
+        let trimmed = line.trim();
+
+        if let Some(close) = delimiter {
+            if close.is_empty() && trimmed.is_empty() {
+                output.extend(["```".to_string(), line]);
+                delimiter = None;
+            } else if !close.is_empty() && trimmed == close {
+                output.push("```".into());
+                delimiter = None;
+            } else {
+                output.push(line);
+            }
+            continue;
+        }
+
+        if let Some((cells, columns)) = table.as_mut() {
+            if trimmed == "|===" {
+                let columns = (*columns).max(1);
+                let rows: Vec<Vec<String>> =
+                    cells.chunks(columns).map(<[String]>::to_vec).collect();
+                output.extend(convert::table(&rows));
+                table = None;
+            } else if let Some(row) = trimmed.strip_prefix('|') {
+                let row: Vec<String> = row.split('|').map(|cell| inline(cell.trim())).collect();
+                if *columns == 0 {
+                    *columns = row.len();
+                }
+                cells.extend(row);
+            }
+            continue;
+        }
+        if trimmed == "|===" {
+            table = Some((Vec::new(), 0));
+            continue;
+        }
+
+        if let Some(source_lang) = parse_lang(trimmed) {
+            lang = Some(source_lang.to_string());
+            continue;
+        }
+        if matches!(trimmed, "----" | "....") {
+            output.push(format!("```{}", lang.take().unwrap_or_default()));
+            delimiter = Some(if trimmed == "----" { "----" } else { "...." });
+            continue;
+        }
+        if let Some(lang) = lang.take().filter(|_| !trimmed.is_empty()) {
+            // A source block without delimiters ends at the next blank line.
+            output.extend([format!("```{lang}"), line]);
+            delimiter = Some("");
+            continue;
+        }
+
+        let is_attribute_entry = trimmed.starts_with(':')
+            && trimmed[1..]
+                .split_once(':')
+                .is_some_and(|(name, _)| !name.is_empty() && !name.contains(' '));
+        if trimmed.starts_with("//")
+            || is_attribute_entry
+            || (trimmed.starts_with('[') && trimmed.ends_with(']'))
+        {
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|&c| c == '=').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            output.push(format!(
+                "{} {}",
+                "#".repeat(level),
+                inline(trimmed[level..].trim())
+            ));
+            continue;
+        }
+
+        // Unordered lists like `* item` or `** nested`, and ordered lists like `. item`.
+        let marker = trimmed.chars().next().filter(|c| *c == '*' || *c == '.');
+        let depth = marker.map_or(0, |marker| {
+            trimmed.chars().take_while(|&c| c == marker).count()
+        });
+        if let Some(item) = marker.and_then(|_| trimmed[depth..].strip_prefix(' ')) {
+            let bullet = if marker == Some('*') { "-" } else { "1." };
+            output.push(format!(
+                "{}{bullet} {}",
+                "   ".repeat(depth - 1),
+                inline(item.trim())
+            ));
+            continue;
+        }
+
+        // A block title like `.Example` before a block.
+        if let Some(title) = trimmed
+            .strip_prefix('.')
+            .filter(|title| title.starts_with(|c: char| c.is_alphanumeric()))
+        {
+            output.push(format!("**{}**", inline(title)));
+            continue;
+        }
+
+        if let Some((label, text)) = ["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"]
+            .into_iter()
+            .find_map(|label| {
+                trimmed
+                    .strip_prefix(label)?
+                    .strip_prefix(": ")
+                    .map(|text| (label, text))
+            })
+        {
+            let label = format!("{}{}", &label[..1], label[1..].to_ascii_lowercase());
+            output.push(format!("> **{label}:** {}", inline(text)));
+            continue;
+        }
+
+        output.push(inline(&line));
+    }
+
+    if delimiter.is_some() {
+        output.push("```".into());
+    }
+    Ok(output)
+}
+
+/// Converts links and constrained emphasis like `*bold*` and `_italic_` to Markdown.
+fn inline(line: &str) -> String {
+    convert::inline(&convert_links(line), &[('*', "**"), ('_', "*")])
+}
+
+/// Converts links like `link:target[text]` or `https://example.com[text]` to Markdown links.
+fn convert_links(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some((start, prefix)) = ["link:", "https://", "http://"]
+        .into_iter()
+        .filter_map(|prefix| rest.find(prefix).map(|i| (i, prefix)))
+        .min()
+    {
+        output.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let target_start = if prefix == "link:" { prefix.len() } else { 0 };
+        let target_end = after[target_start..]
+            .find(|c: char| c == '[' || c.is_whitespace())
+            .map_or(after.len(), |i| target_start + i);
+        let target = &after[target_start..target_end];
+        let text = after[target_end..]
+            .strip_prefix('[')
+            .and_then(|s| s.find(']').map(|end| &s[..end]));
+        match text {
+            Some(text) => {
+                let label = if text.is_empty() { target } else { text };
+                output.push_str(&format!("[{label}]({target})"));
+                rest = &after[target_end + text.len() + 2..];
+            }
+            None => {
+                output.push_str(&after[..target_end]);
+                rest = &after[target_end..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn parse_lang(line: &str) -> Option<&str> {
+    // Look for the language after the source style in the attribute line
+    // Examples: [source,rust,id="example"]
+    //           [,rust]
+    let attrs = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (style, rest) = attrs.split_once(',')?;
+    if !matches!(style.trim(), "" | "source") {
+        return None;
+    }
+    let lang = rest.split(',').next()?.trim();
+    (!lang.is_empty() && !lang.contains('=')).then_some(lang)
+}
+
+fn parse_id(line: &str) -> Option<&str> {
+    // Look for id="name" in the attribute line
+    // Examples: [source,rust,id="example"]
+    //           [,rust,id="example"]
+
+    let id_pos = line.find("id=")?;
+    let after_id = &line[id_pos + 3..];
+
+    // Check if id value is quoted and find closing quote
+    let after_quote = after_id.strip_prefix('"')?;
+    let end_quote = after_quote.find('"')?;
+    Some(&after_quote[..end_quote])
+}
+
+fn parse_file(line: &str) -> Option<&str> {
+    // Look for file=path or file="path" in the attribute line
+    // Example: [source,rust,file="src/example.rs"]
+    let attrs = line.strip_prefix('[')?.strip_suffix(']')?;
+    let path = attrs
+        .split(',')
+        .find_map(|attr| attr.trim().strip_prefix("file="))?;
+    let path = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path);
+    (!path.is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, collect_lang, to_markdown};
+    use crate::{extract, Selector};
+    use std::io::{self, BufRead};
+
+    #[test]
+    fn extract_no_source_blocks() {
+        let content = r#"This is an AsciiDoc file
+with no source blocks at all.
+Just plain text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_no_matching_id() {
+        let content = r#"Some text here.
+
+[source,rust]
+----
+fn main() {
+    println!("Hello");
+}
+----
+
+More text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_source_rust_with_delimiters() {
+        let content = r#"Some introduction text.
+
+[source,rust,id="example"]
+----
+fn main() {
+    println!("Hello, world!");
+}
+----
+
+Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn main() {
+    println!("Hello, world!");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_shorthand_rust_with_delimiters() {
+        let content = r#"Some introduction text.
+
+[,rust,id="example"]
+----
+fn test() {
+    assert_eq!(2 + 2, 4);
+}
+----
+
+Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    assert_eq!(2 + 2, 4);
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_source_rust_without_delimiters() {
+        let content = r#"Some introduction text.
+
+[source,rust,id="example"]
+let x = 42;
+let y = x + 1;
+
+This text should not be included.
+More text here."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let x = 42;
+let y = x + 1;"#
+        );
+    }
+
+    #[test]
+    fn extract_shorthand_rust_without_delimiters() {
+        let content = r#"Some introduction text.
+
+[,rust,id="example"]
+fn inline() {
+    println!("No delimiters");
+}
+
+This text after the blank line should not be included.
+Neither should this."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn inline() {
+    println!("No delimiters");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_multiple_blocks_one_match() {
+        let content = r#"Here's the first block:
+
+[source,python,id="other"]
+----
+print("Not this one")
+----
+
+And here's the one we want:
+
+[,rust,id="example"]
+----
+fn main() {
+    println!("This is the one!");
+}
+----
+
+And another one:
+
+[source,java]
+----
+System.out.println("Also not this one");
+----"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn main() {
+    println!("This is the one!");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_nested_delimiters() {
+        let content = r#"Outer content:
+
+[source,rust,id="example"]
+----
+// Comment with ---- in it
+fn nested() {
+    let s = "----";
+    println!("{}", s);
+}
+----
+
+After the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"// Comment with ---- in it
+fn nested() {
+    let s = "----";
+    println!("{}", s);
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_id_in_middle() {
+        let content = r#"Text before.
+
+[,rust,id="example",role="highlight"]
+----
+fn with_attributes() {}
+----
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, "fn with_attributes() {}");
+    }
+
+    #[test]
+    fn extract_empty_lines_within_delimiters() {
+        let content = r#"Text before.
+
+[,rust,id="example"]
+----
+fn first() {}
+
+fn second() {}
+----
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn first() {}
+
+fn second() {}"#
+        );
+    }
+
+    #[test]
+    fn extract_within_outer_code_block() {
+        let content = r####"Text before.
+
+[,asciidoc]
+----
+[,rust,id="example"]
+let m = example()?;
+assert_eq!(format!("{m:?}"), r#"Model { name: "example" }"#);
+----
+
+Text after."####;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r###"let m = example()?;
+assert_eq!(format!("{m:?}"), r#"Model { name: "example" }"#);"###
+        );
+    }
+
+    #[test]
+    fn extract_start_line_with_delimiters() {
+        // Attribute on line 3, ---- on line 4, first content on line 5.
+        let content = "Text.\n\n[,rust,id=\"example\"]\n----\nlet x = 1;\n----\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 5);
+    }
+
+    #[test]
+    fn extract_start_line_without_delimiters() {
+        // Attribute on line 3, first content line is line 4.
+        let content = "Text.\n\n[,rust,id=\"example\"]\nlet x = 1;\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 4);
+    }
+
+    #[test]
+    fn collect_open_line() {
+        // The opening ---- delimiter, or the attribute line without delimiters.
+        for (content, open_line) in [
+            (
+                "Text.\n\n[,rust,id=\"example\"]\n----\nlet x = 1;\n----\n",
+                4,
+            ),
+            ("Text.\n\n[,rust,id=\"example\"]\nlet x = 1;\n", 3),
+        ] {
+            let blocks = collect(io::BufReader::new(io::Cursor::new(content)).lines())
+                .expect("expected blocks");
+            assert_eq!(blocks[0].open_line, open_line);
+        }
+    }
+
+    #[test]
+    fn extract_index() {
+        let content = "[,rust,id=\"first\"]\n----\nlet x = 1;\n----\n\n[source,rust]\n----\nlet y = 2;\n----\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, result) =
+            extract(cursor, Selector::Index(2), collect).expect("expected content");
+        assert_eq!(start_line, 8);
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn extract_lines() {
+        let content = "[,rust]\n----\nlet x = 1;\nlet y = 2;\n----\n";
+        let cursor = io::Cursor::new(content);
+        let (_, result) =
+            extract(cursor, Selector::Lines(4..5), collect).expect("expected content");
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn collect_untested() {
+        let content = "[,rust,id=\"example\",untested]\n----\nlet x = 1;\n----\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].name(), Some("example"));
+        assert!(blocks[0].untested);
+    }
+
+    #[test]
+    fn collect_tangle() {
+        let content = "[,rust,id=\"example\",file=\"src/example.rs\"]\n----\nlet x = 1;\n----\n\n[,rust,file=src/other.rs]\nlet y = 2;\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].name(), Some("example"));
+        assert_eq!(blocks[0].tangle.as_deref(), Some("src/example.rs"));
+        assert_eq!(blocks[1].tangle.as_deref(), Some("src/other.rs"));
+    }
+
+    #[test]
+    fn collect_lang_any() {
+        let content = "[source,sh]\n----\ncargo test\n----\n\n[,rust,id=\"example\"]\nlet x = 1;\n\n[,toml]\n----\n[package]\n";
+        let blocks = collect_lang(io::BufReader::new(io::Cursor::new(content)).lines(), None)
+            .expect("expected blocks");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].lang.as_deref(), Some("sh"));
+        assert_eq!(blocks[1].lang.as_deref(), Some("rust"));
+        assert_eq!(blocks[1].name(), Some("example"));
+        assert!(!blocks[1].unterminated);
+        assert_eq!(blocks[2].lang.as_deref(), Some("toml"));
+        assert!(blocks[2].unterminated);
+    }
+
+    #[test]
+    fn to_markdown_sections() {
+        let content = r#"= Title
+:toc:
+
+// A comment.
+== Usage
+
+See link:../LICENSE.txt[MIT] or https://docs.rs[docs.rs].
+
+[,rust,id="example"]
+----
+// Not a comment.
+let x = 1;
+----
+
+[source,sh]
+cargo test
+
+....
+literal
+....
+"#;
+        let actual = to_markdown(io::BufReader::new(io::Cursor::new(content)).lines())
+            .expect("expected Markdown");
+        assert_eq!(
+            actual.join("\n"),
+            r#"# Title
+
+## Usage
+
+See [MIT](../LICENSE.txt) or [docs.rs](https://docs.rs).
+
+```rust
+// Not a comment.
+let x = 1;
+```
+
+```sh
+cargo test
+```
+
+```
+literal
+```"#
+        );
+    }
+
+    #[test]
+    fn to_markdown_prose() {
+        let content = r#"== Lists
+
+* *Bold* and _italic_ with `code`
+** Nested
+. First
+. Second
+
+.Options
+[cols="1,2"]
+|===
+|Name |Description
+
+|`scope`
+|Include in *braces*.
+|===
+
+NOTE: See https://docs.rs.
+"#;
+        let actual = to_markdown(io::BufReader::new(io::Cursor::new(content)).lines())
+            .expect("expected Markdown");
+        assert_eq!(
+            actual.join("\n"),
+            r#"## Lists
+
+- **Bold** and *italic* with `code`
+   - Nested
+1. First
+1. Second
+
+**Options**
+| Name | Description |
+| --- | --- |
+| `scope` | Include in **braces**. |
+
+> **Note:** See https://docs.rs."#
+        );
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+//! Helpers shared by formats converting to Markdown.
+
+/// Converts constrained inline markup like `*bold*` to Markdown, outside code spans, link targets, and URLs.
+///
+/// Each marker is replaced with its Markdown equivalent e.g., `('*', "**")`. Content within a marker
+/// replaced by `` ` `` is not converted further.
+pub fn inline(line: &str, markers: &[(char, &str)]) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut prev: Option<char> = None;
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        let at_word_start = prev.is_none_or(|prev| prev.is_whitespace() || "([{\"'".contains(prev));
+
+        // Copy code spans, link targets, and URLs as-is.
+        let verbatim = if c == '`' {
+            rest[1..].find('`').map(|end| end + 2)
+        } else if rest.starts_with("](") {
+            rest.find(')').map(|end| end + 1)
+        } else if at_word_start {
+            let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            rest[..word].contains("://").then_some(word)
+        } else {
+            None
+        };
+        if let Some(len) = verbatim {
+            output.push_str(&rest[..len]);
+            prev = rest[..len].chars().last();
+            i += len;
+            continue;
+        }
+
+        if let Some((_, replacement)) = markers
+            .iter()
+            .find(|(marker, _)| *marker == c)
+            .filter(|_| at_word_start)
+        {
+            if let Some(end) = closing(&rest[c.len_utf8()..], c) {
+                let inner = &rest[c.len_utf8()..c.len_utf8() + end];
+                let inner = if *replacement == "`" {
+                    inner.to_string()
+                } else {
+                    inline(inner, markers)
+                };
+                output.push_str(replacement);
+                output.push_str(&inner);
+                output.push_str(replacement);
+                prev = Some(c);
+                i += end + 2 * c.len_utf8();
+                continue;
+            }
+        }
+
+        output.push(c);
+        prev = Some(c);
+        i += c.len_utf8();
+    }
+    output
+}
+
+/// Returns the position of the `marker` closing constrained markup at the start of `s`.
+fn closing(s: &str, marker: char) -> Option<usize> {
+    if s.starts_with(char::is_whitespace) {
+        return None;
+    }
+    s.char_indices().skip(1).find_map(|(i, c)| {
+        let before = s[..i].chars().last()?;
+        let after = s[i + c.len_utf8()..].chars().next();
+        (c == marker
+            && !before.is_whitespace()
+            && after.is_none_or(|after| after.is_whitespace() || ".,;:!?)]}\"'".contains(after)))
+        .then_some(i)
+    })
+}
+
+/// Renders rows of cells as a Markdown table with the first row as the header.
+pub fn table(rows: &[Vec<String>]) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let mut output = Vec::with_capacity(rows.len() + 1);
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<_> = (0..columns)
+            .map(|column| {
+                row.get(column)
+                    .map_or("", String::as_str)
+                    .replace('|', "\\|")
+            })
+            .collect();
+        output.push(format!("| {} |", cells.join(" | ")));
+        if i == 0 {
+            output.push(format!("|{}", " --- |".repeat(columns)));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inline, table};
+
+    #[test]
+    fn inline_markers() {
+        let markers = [('*', "**"), ('_', "*"), ('=', "`")];
+        assert_eq!(
+            inline(
+                "Use *bold*, _italic_, and =code_with_underscores=.",
+                &markers
+            ),
+            "Use **bold**, *italic*, and `code_with_underscores`."
+        );
+        assert_eq!(
+            inline("*_nested_* in snake_case_name and 2 * 3 * 4", &markers),
+            "***nested*** in snake_case_name and 2 * 3 * 4"
+        );
+        assert_eq!(
+            inline(
+                "See `*literal*`, [a_b](https://example.com/_x_) or https://example.com/_y_.",
+                &markers
+            ),
+            "See `*literal*`, [a_b](https://example.com/_x_) or https://example.com/_y_."
+        );
+    }
+
+    #[test]
+    fn table_rows() {
+        let rows = vec![
+            vec!["Name".to_string(), "Description".to_string()],
+            vec!["a|b".to_string()],
+        ];
+        assert_eq!(
+            table(&rows),
+            vec!["| Name | Description |", "| --- | --- |", "| a\\|b |  |",]
+        );
+    }
+}
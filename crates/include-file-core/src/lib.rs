@@ -0,0 +1,697 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+//! Parsers shared by the [`include-file`](https://crates.io/crates/include-file) macros and tools
+//! to find code blocks within AsciiDoc, Markdown, Org, and Textile files.
+
+#[cfg(feature = "asciidoc")]
+pub mod asciidoc;
+#[cfg(any(feature = "asciidoc", feature = "org", feature = "textile"))]
+mod convert;
+pub mod markdown;
+#[cfg(feature = "org")]
+pub mod org;
+#[cfg(feature = "textile")]
+pub mod textile;
+
+use sha2::{Digest, Sha256};
+use std::{
+    fmt, fs,
+    io::{self, BufRead},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+/// A code block found within a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Block {
+    /// The language of the block e.g., `rust`.
+    pub lang: Option<String>,
+    /// Names or other identifiers used to select the block.
+    pub names: Vec<String>,
+    /// The 1-based line number of the line opening the block e.g., a code fence, or of the first line if there is no delimiter.
+    pub open_line: u32,
+    /// The 1-based line number of the first line of content.
+    pub start_line: u32,
+    /// The lines of content.
+    pub lines: Vec<String>,
+    /// The number of bytes of indentation stripped from each line of content e.g., of a Markdown code fence within a list item.
+    pub indent: usize,
+    /// Whether the block opted out of coverage checks with an `untested` attribute.
+    pub untested: bool,
+    /// Whether the block was not closed before the end of the file.
+    pub unterminated: bool,
+    /// The path the block is written to by [`tangle`], relative to the output directory.
+    ///
+    /// This is `yes` for an Org `:tangle yes` block, which [`tangle`] writes to a file named after the Org file.
+    pub tangle: Option<String>,
+}
+
+impl Block {
+    /// Whether `name` is any of the block's names.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+
+    /// The name of the block, ignoring attributes like `ignore` or `untested` in a Markdown info string.
+    pub fn name(&self) -> Option<&str> {
+        const ATTRIBUTES: &[&str] = &[
+            "compile_fail",
+            "ignore",
+            "no_run",
+            "should_panic",
+            "standalone_crate",
+            "test_harness",
+            "untested",
+        ];
+        self.names
+            .iter()
+            .map(String::as_str)
+            .find(|name| !ATTRIBUTES.contains(name) && !name.starts_with("edition"))
+    }
+
+    /// The 1-based line number of the last line of content.
+    pub fn end_line(&self) -> u32 {
+        (self.start_line + self.lines.len() as u32).saturating_sub(1)
+    }
+
+    /// Returns the error for an unterminated block opened within the file displayed as `display_path`.
+    pub fn unterminated_error(&self, display_path: &str) -> io::Error {
+        let name = self
+            .name()
+            .map(|name| format!(" '{name}'"))
+            .unwrap_or_default();
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "unterminated code fence{name} opened at {display_path}:{}",
+                self.open_line
+            ),
+        )
+    }
+}
+
+/// Selects which code block to include.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Selector {
+    /// Select the first Rust code block with the given name.
+    Name(String),
+    /// Select the Rust code block by its 1-based ordinal in the file.
+    Index(usize),
+    /// Select a range of lines within a single Rust code block.
+    Lines(Range<u32>),
+}
+
+impl Selector {
+    /// Returns the index of the selected block within `blocks`.
+    pub fn find(&self, blocks: &[Block]) -> io::Result<usize> {
+        match self {
+            Self::Name(name) => blocks
+                .iter()
+                .position(|block| block.is_named(name))
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("{self} not found"))
+                }),
+            Self::Index(index) => index
+                .checked_sub(1)
+                .filter(|i| *i < blocks.len())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!(
+                            "code fence index {index} out of range; found {} Rust code fences",
+                            blocks.len()
+                        ),
+                    )
+                }),
+            Self::Lines(range) => {
+                let i = blocks
+                    .iter()
+                    .position(|block| (block.start_line..=block.end_line()).contains(&range.start))
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("no Rust code fence contains line {}", range.start),
+                        )
+                    })?;
+                let end_line = blocks[i].end_line();
+                if range.end - 1 > end_line {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "line range {range:?} crosses the end of the code fence at line {end_line}"
+                        ),
+                    ));
+                }
+                Ok(i)
+            }
+        }
+    }
+
+    /// Extracts the content of the selected block within `blocks` and the 1-based line number where it starts.
+    pub fn extract(&self, blocks: &[Block]) -> io::Result<(u32, String)> {
+        let block = &blocks[self.find(blocks)?];
+        let (start_line, lines) = match self {
+            Self::Lines(range) => {
+                let skip = (range.start - block.start_line) as usize;
+                (range.start, &block.lines[skip..skip + range.len()])
+            }
+            _ => (block.start_line, &block.lines[..]),
+        };
+
+        Ok((start_line, lines.join("\n")))
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "code fence '{name}'"),
+            Self::Index(index) => write!(f, "code fence index {index}"),
+            Self::Lines(range) => write!(f, "line range {range:?}"),
+        }
+    }
+}
+
+/// Which code blocks to select when more than one in a file has the selected name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+    First,
+    Last,
+    /// Every block with the name in order.
+    All,
+}
+
+impl FromStr for Duplicates {
+    type Err = String;
+
+    /// Parses `"first"`, `"last"`, or `"all"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Self::First),
+            "last" => Ok(Self::Last),
+            "all" => Ok(Self::All),
+            _ => Err(r#"expected "first", "last", or "all""#.into()),
+        }
+    }
+}
+
+/// Parses a 1-based line range like `"40..55"` or `"40..=54"` into an exclusive range.
+pub fn parse_range(s: &str) -> Result<Range<u32>, String> {
+    const INVALID_RANGE: &str = r#"expected a line range like "40..55" or "40..=54""#;
+
+    let (start, end, inclusive) = match s.split_once("..=") {
+        Some((start, end)) => (start, end, true),
+        None => {
+            let (start, end) = s.split_once("..").ok_or(INVALID_RANGE)?;
+            (start, end, false)
+        }
+    };
+    let start: u32 = start.trim().parse().map_err(|_| INVALID_RANGE)?;
+    let mut end: u32 = end.trim().parse().map_err(|_| INVALID_RANGE)?;
+    if inclusive {
+        end = end.checked_add(1).ok_or(INVALID_RANGE)?;
+    }
+    if start == 0 {
+        return Err("line numbers are 1-based".into());
+    }
+    if start >= end {
+        return Err(format!("line range {s} is empty"));
+    }
+    Ok(start..end)
+}
+
+impl From<&str> for Selector {
+    fn from(name: &str) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+/// The format of a file containing code blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// An AsciiDoc file with an `.adoc`, `.asciidoc`, or `.asc` extension.
+    #[cfg(feature = "asciidoc")]
+    Asciidoc,
+    /// A Markdown file with an `.md`, `.markdown`, `.mdown`, or `.mkdn` extension.
+    Markdown,
+    /// An Org file with an `.org` extension.
+    #[cfg(feature = "org")]
+    Org,
+    /// A Textile file with a `.textile` extension.
+    #[cfg(feature = "textile")]
+    Textile,
+}
+
+impl Format {
+    /// Returns the format of a file based on its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        match ext.as_deref() {
+            #[cfg(feature = "asciidoc")]
+            Some("adoc" | "asciidoc" | "asc") => Ok(Self::Asciidoc),
+            Some("markdown" | "mdown" | "mkdn" | "md") => Ok(Self::Markdown),
+            #[cfg(feature = "org")]
+            Some("org") => Ok(Self::Org),
+            #[cfg(feature = "textile")]
+            Some("textile") => Ok(Self::Textile),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported file extension for {}", path.display()),
+            )),
+        }
+    }
+
+    /// Collects code blocks in `lang` from `reader`, or all code blocks if `lang` is `None`.
+    pub fn collect<R: io::Read>(self, reader: R, lang: Option<&str>) -> io::Result<Vec<Block>> {
+        let lines = io::BufReader::new(reader).lines();
+        match self {
+            #[cfg(feature = "asciidoc")]
+            Self::Asciidoc => asciidoc::collect_lang(lines, lang),
+            Self::Markdown => markdown::collect_lang(lines, lang),
+            #[cfg(feature = "org")]
+            Self::Org => org::collect_lang(lines, lang),
+            #[cfg(feature = "textile")]
+            Self::Textile => textile::collect_lang(lines, lang),
+        }
+    }
+
+    /// Reads `reader` as Markdown, converting AsciiDoc, Org, and Textile content to Markdown.
+    pub fn to_markdown<R: io::Read>(self, reader: R) -> io::Result<Vec<String>> {
+        let lines = io::BufReader::new(reader).lines();
+        match self {
+            #[cfg(feature = "asciidoc")]
+            Self::Asciidoc => asciidoc::to_markdown(lines),
+            Self::Markdown => lines.collect(),
+            #[cfg(feature = "org")]
+            Self::Org => org::to_markdown(lines),
+            #[cfg(feature = "textile")]
+            Self::Textile => textile::to_markdown(lines),
+        }
+    }
+
+    /// Returns the section under the heading `title` from `reader` as Markdown, including the heading and any subsections.
+    pub fn section<R: io::Read>(self, reader: R, title: &str) -> io::Result<Vec<String>> {
+        let lines = self.to_markdown(reader)?;
+        markdown::section(&lines, title)
+            .map(<[String]>::to_vec)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("section '{title}' not found"),
+                )
+            })
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = io::Error;
+
+    /// Parses the name of a format e.g., `"markdown"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            #[cfg(feature = "asciidoc")]
+            "asciidoc" => Ok(Self::Asciidoc),
+            "markdown" => Ok(Self::Markdown),
+            #[cfg(feature = "org")]
+            "org" => Ok(Self::Org),
+            #[cfg(feature = "textile")]
+            "textile" => Ok(Self::Textile),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported format {s}"),
+            )),
+        }
+    }
+}
+
+/// Extracts the content of the Rust code block selected by `selector` and the 1-based line number where it starts.
+pub fn extract<R, F>(buffer: R, selector: impl Into<Selector>, f: F) -> io::Result<(u32, String)>
+where
+    R: io::Read,
+    F: FnOnce(io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>>,
+{
+    let reader = io::BufReader::new(buffer);
+    let blocks = f(reader.lines())?;
+    selector.into().extract(&blocks)
+}
+
+/// Returns the start line and content of each block selected by `selector` exactly as the macros include them.
+///
+/// Fails if a selected block or an earlier block that may hide it is unterminated, or if more than one block has the
+/// selected name and `duplicates` is not passed. The `display_path` of the file is used in error messages.
+pub fn select(
+    selector: &Selector,
+    duplicates: Option<Duplicates>,
+    blocks: &[Block],
+    display_path: &str,
+) -> io::Result<Vec<(u32, String)>> {
+    if duplicates.is_some() && !matches!(selector, Selector::Name(_)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(r#"{selector} selects one code fence; "duplicates" requires a "name""#),
+        ));
+    }
+
+    let selected: Vec<&Block> = match selector {
+        Selector::Name(name) => blocks.iter().filter(|block| block.is_named(name)).collect(),
+        _ => selector
+            .find(blocks)
+            .map(|i| vec![&blocks[i]])
+            .unwrap_or_default(),
+    };
+
+    // An unterminated block consumes the rest of the file, so any block not found may be within it.
+    if let Some(block) = blocks.iter().find(|block| block.unterminated) {
+        if selected.is_empty() || selected.iter().any(|block| block.unterminated) {
+            return Err(block.unterminated_error(display_path));
+        }
+    }
+
+    let selected = match (selected.len(), duplicates) {
+        (0 | 1, _) => return selector.extract(blocks).map(|block| vec![block]),
+        (_, None) => {
+            let locations: Vec<_> = selected
+                .iter()
+                .map(|block| format!("{display_path}:{}", block.start_line))
+                .collect();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{selector} is ambiguous; found in {}", locations.join(", ")),
+            ));
+        }
+        (_, Some(Duplicates::First)) => &selected[..1],
+        (n, Some(Duplicates::Last)) => &selected[n - 1..],
+        (_, Some(Duplicates::All)) => &selected[..],
+    };
+    Ok(selected
+        .iter()
+        .map(|block| (block.start_line, block.lines.join("\n")))
+        .collect())
+}
+
+/// Returns the SHA-256 hash of the `content` of a code block as lowercase hexadecimal, as pinned by `sha256 = ".."`.
+///
+/// The content is the lines of the block joined by `\n` without a trailing new line, as [`extract`] returns it.
+pub fn sha256(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Writes code blocks with a target path from the file at `path` into `out_dir`, returning the paths written.
+///
+/// Blocks are marked with `:tangle path` in Org, `file=path` in a Markdown info string, or a `file=path` AsciiDoc attribute.
+/// Like Emacs, Org blocks marked with `:tangle yes` are written to a file named after the Org file with an extension
+/// for the block's language e.g., `design.rs` for a `rust` block in `design.org`.
+/// Blocks with the same target are written to the same file in order. Files are only written when their content changes.
+/// An unterminated block is an error with the line that opened it.
+///
+/// Meant to be called from a build script, this prints `cargo::rerun-if-changed` for `path`.
+///
+/// # Examples
+///
+/// In `build.rs`:
+///
+/// ```no_run
+/// fn main() -> std::io::Result<()> {
+///     let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR");
+///     include_file_core::tangle("docs/design.org", out_dir)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Then include a block marked with `:tangle design/model.rs` as a module:
+///
+/// ```ignore
+/// mod model {
+///     include!(concat!(env!("OUT_DIR"), "/design/model.rs"));
+/// }
+/// ```
+pub fn tangle(path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    println!("cargo::rerun-if-changed={}", path.display());
+
+    let format = Format::from_path(path)?;
+    let blocks = format.collect(fs::File::open(path)?, None)?;
+    // An unterminated block consumes the rest of the file, including any blocks to write after it.
+    if let Some(block) = blocks.iter().find(|block| block.unterminated) {
+        return Err(block.unterminated_error(&path.display().to_string()));
+    }
+    let mut files: Vec<(String, String)> = Vec::new();
+    for block in &blocks {
+        let Some(target) = block.tangle.as_deref() else {
+            continue;
+        };
+        let target = match format {
+            #[cfg(feature = "org")]
+            Format::Org if target == "yes" => org::tangle_target(path, block.lang.as_deref())?,
+            _ => target.to_string(),
+        };
+        let i = match files.iter().position(|(t, _)| *t == target) {
+            Some(i) => {
+                files[i].1.push('\n');
+                i
+            }
+            None => {
+                files.push((target, String::new()));
+                files.len() - 1
+            }
+        };
+        for line in &block.lines {
+            files[i].1.push_str(line);
+            files[i].1.push('\n');
+        }
+    }
+
+    let out_dir = out_dir.as_ref();
+    let mut written = Vec::with_capacity(files.len());
+    for (target, content) in files {
+        // Targets must stay within `out_dir`.
+        let target_path = Path::new(&target);
+        if !target_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "tangle target {target} must be a relative path within the output directory"
+                ),
+            ));
+        }
+        let target_path = out_dir.join(target_path);
+        if fs::read_to_string(&target_path).ok().as_deref() != Some(content.as_str()) {
+            if let Some(dir) = target_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&target_path, content)?;
+        }
+        written.push(target_path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_range, select, sha256, tangle, Block, Duplicates, Selector};
+    use std::{env, fs, io};
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(parse_range("40..55"), Ok(40..55));
+        assert_eq!(parse_range("40..=55"), Ok(40..56));
+        assert_eq!(parse_range(" 1 .. 2 "), Ok(1..2));
+        assert!(parse_range("0..2").is_err());
+        assert!(parse_range("2..2").is_err());
+        assert!(parse_range("3..2").is_err());
+        assert!(parse_range("1..").is_err());
+        assert!(parse_range("1-2").is_err());
+    }
+
+    fn blocks() -> Vec<Block> {
+        vec![
+            Block {
+                names: vec!["example".into()],
+                open_line: 2,
+                start_line: 3,
+                lines: vec!["let first = 1;".into()],
+                ..Default::default()
+            },
+            Block {
+                names: vec!["example".into()],
+                open_line: 9,
+                start_line: 10,
+                lines: vec!["let last = first;".into(), "let x = last;".into()],
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn select_duplicates() {
+        let blocks = blocks();
+        let name = Selector::Name("example".into());
+        let err = select(&name, None, &blocks, "README.md").expect_err("expected ambiguous name");
+        assert_eq!(
+            err.to_string(),
+            "code fence 'example' is ambiguous; found in README.md:3, README.md:10"
+        );
+        assert_eq!(
+            select(&name, Some(Duplicates::First), &blocks, "README.md").expect("first"),
+            vec![(3, "let first = 1;".to_string())]
+        );
+        assert_eq!(
+            select(&name, Some(Duplicates::Last), &blocks, "README.md").expect("last"),
+            vec![(10, "let last = first;\nlet x = last;".to_string())]
+        );
+        assert_eq!(
+            select(&name, Some(Duplicates::All), &blocks, "README.md")
+                .expect("all")
+                .len(),
+            2
+        );
+        assert_eq!(
+            select(&Selector::Lines(11..12), None, &blocks, "README.md").expect("lines"),
+            vec![(11, "let x = last;".to_string())]
+        );
+        select(
+            &Selector::Index(1),
+            Some(Duplicates::First),
+            &blocks,
+            "README.md",
+        )
+        .expect_err("expected duplicates to require a name");
+    }
+
+    #[test]
+    fn select_unterminated() {
+        let mut blocks = blocks();
+        blocks[1].unterminated = true;
+        let name = Selector::Name("missing".into());
+        let err = select(&name, None, &blocks, "README.md").expect_err("expected unterminated");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "unterminated code fence 'example' opened at README.md:9"
+        );
+        select(&Selector::Index(1), None, &blocks, "README.md")
+            .expect("expected block before unterminated");
+    }
+
+    #[test]
+    fn sha256_hex() {
+        assert_eq!(
+            sha256("let x = 1;"),
+            "aad7378dfeb851703927a534da47d0d02be7f54cf8c1751a37734d515811dafe"
+        );
+    }
+
+    #[test]
+    fn tangle_markdown() {
+        let dir = env::temp_dir().join(format!("include-file-tangle-{}", std::process::id()));
+        let out_dir = dir.join("out");
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("design.md");
+        fs::write(
+            &path,
+            "```rust file=model.rs\npub struct Model;\n```\n\n```rust example\nlet x = 1;\n```\n\n```rust file=model.rs\nimpl Model {}\n```\n\n```toml file=nested/config.toml\n[package]\n```\n",
+        )
+        .expect("write temp file");
+
+        let written = tangle(&path, &out_dir).expect("expected tangle");
+        assert_eq!(
+            written,
+            vec![out_dir.join("model.rs"), out_dir.join("nested/config.toml")]
+        );
+        assert_eq!(
+            fs::read_to_string(&written[0]).expect("read model.rs"),
+            "pub struct Model;\n\nimpl Model {}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&written[1]).expect("read config.toml"),
+            "[package]\n"
+        );
+
+        fs::write(&path, "```rust file=../escape.rs\nlet x = 1;\n```\n").expect("write temp file");
+        let err = tangle(&path, &out_dir).expect_err("expected escaping target");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        fs::write(&path, "```rust file=model.rs\npub struct Model;\n").expect("write temp file");
+        let err = tangle(&path, &out_dir).expect_err("expected unterminated block");
+        assert_eq!(
+            err.to_string(),
+            format!("unterminated code fence opened at {}:1", path.display())
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "asciidoc")]
+    #[test]
+    fn tangle_asciidoc() {
+        let dir = env::temp_dir().join(format!(
+            "include-file-tangle-asciidoc-{}",
+            std::process::id()
+        ));
+        let out_dir = dir.join("out");
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("design.adoc");
+        fs::write(
+            &path,
+            "[,rust,file=\"model.rs\"]\n----\npub struct Model;\n----\n\n[,rust,id=example]\n----\nlet x = 1;\n----\n\n[,rust,file=model.rs]\n----\nimpl Model {}\n----\n",
+        )
+        .expect("write temp file");
+
+        let written = tangle(&path, &out_dir).expect("expected tangle");
+        assert_eq!(written, vec![out_dir.join("model.rs")]);
+        assert_eq!(
+            fs::read_to_string(&written[0]).expect("read model.rs"),
+            "pub struct Model;\n\nimpl Model {}\n"
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(feature = "org")]
+    #[test]
+    fn tangle_org() {
+        let dir = env::temp_dir().join(format!("include-file-tangle-org-{}", std::process::id()));
+        let out_dir = dir.join("out");
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("design.org");
+        fs::write(
+            &path,
+            "#+BEGIN_SRC rust :tangle nested/model.rs\npub struct Model;\n#+END_SRC\n\n#+BEGIN_SRC rust :tangle no\nlet x = 1;\n#+END_SRC\n\n#+BEGIN_SRC rust :tangle yes\nfn main() {}\n#+END_SRC\n",
+        )
+        .expect("write temp file");
+
+        let written = tangle(&path, &out_dir).expect("expected tangle");
+        assert_eq!(
+            written,
+            vec![out_dir.join("nested/model.rs"), out_dir.join("design.rs")]
+        );
+        assert_eq!(
+            fs::read_to_string(&written[0]).expect("read model.rs"),
+            "pub struct Model;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&written[1]).expect("read design.rs"),
+            "fn main() {}\n"
+        );
+
+        fs::write(&path, "#+BEGIN_SRC :tangle yes\nplain\n#+END_SRC\n").expect("write temp file");
+        let err = tangle(&path, &out_dir).expect_err("expected missing language");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,750 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::Block;
+use std::io;
+
+/// Collects Rust code fences.
+pub fn collect<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    collect_lang(iter, Some("rust"))
+}
+
+/// Collects code fences in `lang`, or all code fences if `lang` is `None`.
+pub fn collect_lang<R: io::Read>(
+    iter: io::Lines<io::BufReader<R>>,
+    lang: Option<&str>,
+) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut block: Option<Block> = None;
+    let mut fence_char = '\0';
+    let mut fence_count = 0;
+    let mut fence_indent = 0;
+
+    for (line_idx, line) in iter.enumerate() {
+        let line = line?;
+        let line_num = (line_idx + 1) as u32;
+
+        let Some(current) = block.as_mut() else {
+            // Look for the start of a code fence
+            let trimmed_start = line.trim_start();
+            let indent = line.len() - trimmed_start.len();
+
+            // Check if line starts with ``` or ~~~
+            let first_char = trimmed_start.chars().next();
+            if first_char == Some('`') || first_char == Some('~') {
+                let fence_ch = first_char.unwrap();
+                let count = trimmed_start.chars().take_while(|&c| c == fence_ch).count();
+
+                if count >= 3 {
+                    // Check if the info string starts with the language followed by the name and other attributes
+                    let mut info = trimmed_start[count..]
+                        .split(|c: char| c.is_whitespace() || c == ',')
+                        .filter(|s| !s.is_empty());
+                    let info_lang = info.next();
+                    if lang.is_none_or(|lang| info_lang == Some(lang)) {
+                        // A `file=path` attribute is the tangle target rather than a name.
+                        let (tangle, names): (Vec<&str>, Vec<&str>) =
+                            info.partition(|name| name.starts_with("file="));
+                        let names: Vec<String> = names.into_iter().map(String::from).collect();
+                        block = Some(Block {
+                            lang: info_lang.map(String::from),
+                            untested: names.iter().any(|name| name == "untested"),
+                            names,
+                            tangle: tangle.first().map(|attr| attr["file=".len()..].to_string()),
+                            open_line: line_num,
+                            start_line: line_num + 1,
+                            indent,
+                            ..Default::default()
+                        });
+                        fence_char = fence_ch;
+                        fence_count = count;
+                        fence_indent = indent;
+                    }
+                }
+            }
+            continue;
+        };
+
+        // We're inside a fence, check if this line ends the fence
+        let trimmed_start = line.trim_start();
+        let indent = line.len() - trimmed_start.len();
+
+        // Check if this line is the closing fence
+        if indent == fence_indent {
+            let first_char = trimmed_start.chars().next();
+            if first_char == Some(fence_char) {
+                let count = trimmed_start
+                    .chars()
+                    .take_while(|&c| c == fence_char)
+                    .count();
+                if count >= fence_count {
+                    // Found the closing fence
+                    blocks.extend(block.take());
+                    continue;
+                }
+            }
+        }
+
+        // Collect the line content, stripping the expected indentation
+        if line.len() >= fence_indent {
+            let content = &line[fence_indent..];
+            current.lines.push(content.to_string());
+        } else {
+            // Line has less indentation than expected, include as-is
+            current.lines.push(line);
+        }
+    }
+
+    // An unterminated fence continues to the end of the file.
+    blocks.extend(block.map(|block| Block {
+        unterminated: true,
+        ..block
+    }));
+
+    Ok(blocks)
+}
+
+/// Returns the lines of the section under the heading `title`, including the heading and any subsections.
+///
+/// The section ends before the next heading of the same or a higher level.
+pub fn section<'a>(lines: &'a [String], title: &str) -> Option<&'a [String]> {
+    let headings = headings(lines);
+    let i = headings.iter().position(|heading| heading.text == title)?;
+    let (start, level) = (headings[i].line, headings[i].level);
+    let end = headings[i + 1..]
+        .iter()
+        .find(|heading| heading.level <= level)
+        .map_or(lines.len(), |heading| heading.line);
+
+    // Trim blank lines before the next section.
+    let end = start
+        + 1
+        + lines[start + 1..end]
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |i| i + 1);
+    Some(&lines[start..end])
+}
+
+/// Demotes every heading by `levels` e.g., `# Usage` becomes `## Usage` when `levels` is 1, up to level 6.
+///
+/// Setext headings underlined with `===` or `---` are converted to ATX headings like `## Usage`.
+pub fn demote(lines: &[String], levels: usize) -> Vec<String> {
+    let headings = headings(lines);
+    let mut output = Vec::with_capacity(lines.len());
+    let mut next = headings.iter().peekable();
+    let mut i = 0;
+    while i < lines.len() {
+        match next.next_if(|heading| heading.line == i) {
+            Some(heading) => {
+                let level = (heading.level + levels).min(6);
+                output.push(format!("{} {}", "#".repeat(level), heading.text));
+                i += if heading.setext { 2 } else { 1 };
+            }
+            None => {
+                output.push(lines[i].clone());
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+/// Removes badges - images within links like `[![CI](https://.../badge.svg)](https://...)` - outside code fences.
+///
+/// Lines left empty after removing badges are removed.
+pub fn drop_badges(lines: &[String]) -> Vec<String> {
+    let code = code_lines(lines);
+    let mut output = Vec::with_capacity(lines.len());
+    for (line, code) in lines.iter().zip(code) {
+        if code {
+            output.push(line.clone());
+            continue;
+        }
+        let mut rest = line.as_str();
+        let mut dropped = String::with_capacity(line.len());
+        while let Some(start) = rest.find("[![") {
+            dropped.push_str(&rest[..start]);
+            match badge_len(&rest[start..]) {
+                Some(len) => rest = &rest[start + len..],
+                None => {
+                    dropped.push_str("[![");
+                    rest = &rest[start + 3..];
+                }
+            }
+        }
+        dropped.push_str(rest);
+        if dropped.len() == line.len() || !dropped.trim().is_empty() {
+            output.push(dropped);
+        }
+    }
+    output
+}
+
+/// Rewrites the targets of inline links and images, and link reference definitions, outside code fences.
+///
+/// Targets are replaced with the value returned by `f`, or left unchanged if `f` returns `None`.
+pub fn rewrite_links(lines: &[String], mut f: impl FnMut(&str) -> Option<String>) -> Vec<String> {
+    let code = code_lines(lines);
+    let mut output = Vec::with_capacity(lines.len());
+    for (line, code) in lines.iter().zip(code) {
+        if code {
+            output.push(line.clone());
+            continue;
+        }
+
+        // A link reference definition like `[label]: target "title"`.
+        let trimmed = line.trim_start();
+        if let Some(after) = trimmed
+            .strip_prefix('[')
+            .and_then(|s| s.split_once("]:"))
+            .map(|(_, after)| after)
+        {
+            let target = after.split_whitespace().next().unwrap_or_default();
+            if let Some(rewritten) = f(target) {
+                let start = line.len() - after.len() + after.find(target).unwrap_or(0);
+                output.push(format!(
+                    "{}{rewritten}{}",
+                    &line[..start],
+                    &line[start + target.len()..]
+                ));
+                continue;
+            }
+        }
+
+        let mut rest = line.as_str();
+        let mut rewritten = String::with_capacity(line.len());
+        while let Some(start) = rest.find("](") {
+            rewritten.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            let len = rest
+                .find(|c: char| c == ')' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let target = &rest[..len];
+            match f(target) {
+                Some(target) => rewritten.push_str(&target),
+                None => rewritten.push_str(target),
+            }
+            rest = &rest[len..];
+        }
+        rewritten.push_str(rest);
+        output.push(rewritten);
+    }
+    output
+}
+
+/// A heading found by [`headings`].
+struct Heading {
+    /// The 0-based index of the heading line.
+    line: usize,
+    level: usize,
+    text: String,
+    /// Whether the heading is underlined on the next line with `===` or `---`.
+    setext: bool,
+}
+
+/// Returns ATX and setext headings outside code fences.
+fn headings(lines: &[String]) -> Vec<Heading> {
+    let code = code_lines(lines);
+    let mut headings = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if code[i] || line.len() - line.trim_start().len() > 3 {
+            continue;
+        }
+        let trimmed = line.trim();
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&level)
+            && trimmed[level..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+        {
+            // Closing sequences like `## Usage ##` are not part of the text.
+            let text = trimmed[level..].trim();
+            let text = match text.trim_end_matches('#') {
+                stripped if stripped.is_empty() || stripped.ends_with(char::is_whitespace) => {
+                    stripped.trim_end()
+                }
+                _ => text,
+            };
+            headings.push(Heading {
+                line: i,
+                level,
+                text: text.to_string(),
+                setext: false,
+            });
+            continue;
+        }
+
+        // A setext heading is a line of text underlined with `=` for level 1 or `-` for level 2.
+        let Some(underline) = lines.get(i + 1).filter(|_| !code[i + 1]).map(|s| s.trim()) else {
+            continue;
+        };
+        let level = match underline.chars().next() {
+            Some('=') if underline.chars().all(|c| c == '=') => 1,
+            Some('-') if underline.chars().all(|c| c == '-') => 2,
+            _ => continue,
+        };
+        let previous_blank = i == 0 || lines[i - 1].trim().is_empty();
+        if !trimmed.is_empty() && previous_blank && !trimmed.starts_with(['-', '*', '>']) {
+            headings.push(Heading {
+                line: i,
+                level,
+                text: trimmed.to_string(),
+                setext: true,
+            });
+        }
+    }
+    headings
+}
+
+/// Returns whether each line is part of a code fence, including the opening and closing fences.
+fn code_lines(lines: &[String]) -> Vec<bool> {
+    let mut code = Vec::with_capacity(lines.len());
+    let mut fence: Option<(char, usize)> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        let ch = trimmed.chars().next().filter(|&c| c == '`' || c == '~');
+        let count = ch.map_or(0, |ch| trimmed.chars().take_while(|&c| c == ch).count());
+        match (fence, ch) {
+            (None, Some(ch)) if count >= 3 => fence = Some((ch, count)),
+            (Some((open, open_count)), Some(ch))
+                if ch == open && count >= open_count && trimmed[count..].trim().is_empty() =>
+            {
+                code.push(true);
+                fence = None;
+                continue;
+            }
+            _ => {}
+        }
+        code.push(fence.is_some());
+    }
+    code
+}
+
+/// Returns the length of a badge like `[![alt](image)](link)` at the start of `s`.
+fn badge_len(s: &str) -> Option<usize> {
+    let image_end = s.find("](")? + 2;
+    let image_end = image_end + s[image_end..].find(')')? + 1;
+    let link = s[image_end..].strip_prefix("](")?;
+    let link_end = link.find(')')? + 1;
+    Some(image_end + 2 + link_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, collect_lang, demote, drop_badges, rewrite_links, section};
+    use crate::{extract, Selector};
+    use std::io::{self, BufRead};
+
+    #[test]
+    fn extract_no_code_fences() {
+        let content = r#"This is a markdown file
+with no code fences at all.
+Just plain text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_no_matching_name() {
+        let content = r#"Some text here.
+
+```rust
+fn main() {
+    println!("Hello");
+}
+```
+
+More text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_multiple_fences_one_match() {
+        let content = r#"Here's the first fence:
+
+```javascript
+console.log("Not this one");
+```
+
+And here's the one we want:
+
+~~~rust example
+fn main() {
+    println!("Hello, world!");
+}
+~~~
+
+And another one:
+
+```python
+print("Also not this one")
+```"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn main() {
+    println!("Hello, world!");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_nested_code_fence() {
+        let content = r#"Outer content:
+
+````markdown
+# Example
+
+Here's a nested code fence:
+
+```rust example
+fn nested() {
+    println!("Inner code");
+}
+```
+
+More content.
+````
+
+After the fence."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn nested() {
+    println!("Inner code");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_with_indentation() {
+        let content = r#"Normal text.
+
+  ~~~rust example
+  fn indented() {
+      println!("Indented code");
+  }
+  ~~~
+
+More text."#;
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].indent, 2);
+
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn indented() {
+    println!("Indented code");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_backticks_with_name() {
+        let content = r#"Text before.
+
+```rust example
+let x = 42;
+let y = x + 1;
+```
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let x = 42;
+let y = x + 1;"#
+        );
+    }
+
+    #[test]
+    fn extract_tildes_with_name() {
+        let content = r#"Text before.
+
+~~~rust example
+fn hello() {
+    println!("Hello");
+}
+~~~
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn hello() {
+    println!("Hello");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_more_closing_chars() {
+        let content = r#"Text before.
+
+```rust example
+fn test() {
+    println!("test");
+}
+`````
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    println!("test");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_rust_with_whitespace() {
+        let content = r#"Text before.
+
+```  rust   example
+let a = 1;
+let b = 2;
+```
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let a = 1;
+let b = 2;"#
+        );
+    }
+
+    #[test]
+    fn extract_no_rust_language() {
+        let content = r#"Text before.
+
+```python example
+def test():
+    pass
+```
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_start_line() {
+        // Opening fence is on line 3; first content line is line 4.
+        let content = "Text before.\n\n```rust example\nlet x = 42;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 4);
+    }
+
+    #[test]
+    fn collect_info_string_names() {
+        // Names are words after the language separated by whitespace or commas, as in rustdoc attributes.
+        let content =
+            "```rust,ignore example\nlet a = 1;\n```\n\n```rust,no_run,other\nlet b = 2;\n```\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].is_named("example"));
+        assert!(blocks[1].is_named("other"));
+    }
+
+    #[test]
+    fn collect_info_string_exact() {
+        // Previously, any info string starting with "rust" and containing the name matched.
+        let content = "```rustdoc example\nlet a = 1;\n```\n\n```rust examples\nlet b = 2;\n```\n\n```rust title=\"example\"\nlet c = 3;\n```\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks.len(), 2, "expected only rust code fences");
+        assert!(blocks.iter().all(|block| !block.is_named("example")));
+        let err = extract(io::Cursor::new(content), "example", collect)
+            .expect_err("expected name not found");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn collect_open_line() {
+        let content = "Text before.\n\n```rust example\nlet x = 42;\n```\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].open_line, 3);
+    }
+
+    #[test]
+    fn extract_start_line_with_preceding_fences() {
+        // The matching fence opens on line 9; first content line is line 10.
+        let content =
+            "Text.\n\n```rust other\nfn a() {}\n```\n\nMore.\n\n```rust example\nlet x = 1;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 10);
+    }
+
+    #[test]
+    fn extract_index() {
+        let content = "Text.\n\n```rust first\nlet x = 1;\n```\n\n```python\nx = 2\n```\n\n```rust\nlet y = 2;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, result) =
+            extract(cursor, Selector::Index(2), collect).expect("expected content");
+        assert_eq!(start_line, 12);
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn extract_index_nested_code_fence() {
+        let content = "````markdown\n```rust example\nlet x = 1;\n```\n````\n";
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, Selector::Index(1), collect).expect("expected content");
+        assert_eq!(result, "let x = 1;");
+    }
+
+    #[test]
+    fn extract_index_out_of_range() {
+        let content = "```rust example\nlet x = 1;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, Selector::Index(2), collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_lines() {
+        let content = "Text.\n\n```rust\nlet a = 1;\nlet b = 2;\nlet c = 3;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, result) =
+            extract(cursor, Selector::Lines(5..7), collect).expect("expected content");
+        assert_eq!(start_line, 5);
+        assert_eq!(result, "let b = 2;\nlet c = 3;");
+    }
+
+    #[test]
+    fn extract_lines_crosses_fence() {
+        let content = "Text.\n\n```rust\nlet a = 1;\nlet b = 2;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, Selector::Lines(4..7), collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::InvalidInput));
+    }
+
+    #[test]
+    fn extract_lines_outside_fence() {
+        let content = "Text.\n\n```rust\nlet a = 1;\n```\n";
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, Selector::Lines(1..2), collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn collect_untested() {
+        let content =
+            "```rust ignore example\nlet x = 1;\n```\n\n```rust untested\nlet y = 2;\n```\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].name(), Some("example"));
+        assert!(!blocks[0].untested);
+        assert_eq!(blocks[1].name(), None);
+        assert!(blocks[1].untested);
+    }
+
+    #[test]
+    fn collect_tangle() {
+        let content =
+            "```rust example file=src/example.rs\nlet x = 1;\n```\n\n```toml\n[package]\n```\n";
+        let blocks = collect_lang(io::BufReader::new(io::Cursor::new(content)).lines(), None)
+            .expect("expected blocks");
+        assert_eq!(blocks[0].names, vec!["example"]);
+        assert_eq!(blocks[0].tangle.as_deref(), Some("src/example.rs"));
+        assert_eq!(blocks[1].tangle, None);
+    }
+
+    #[test]
+    fn collect_lang_any() {
+        let content = "```sh\ncargo test\n```\n\n```\nplain\n```\n\n```rust example\nlet x = 1;\n";
+        let blocks = collect_lang(io::BufReader::new(io::Cursor::new(content)).lines(), None)
+            .expect("expected blocks");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].lang.as_deref(), Some("sh"));
+        assert_eq!(blocks[1].lang, None);
+        assert_eq!(blocks[2].lang.as_deref(), Some("rust"));
+        assert_eq!(blocks[2].name(), Some("example"));
+        assert!(!blocks[1].unterminated);
+        assert!(blocks[2].unterminated);
+    }
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn section_atx() {
+        let content = lines(
+            "# Title\n\nIntro.\n\n## Usage\n\nUse it.\n\n```sh\n# not a heading\n```\n\n### Details\n\nMore.\n\n## License ##\n\nMIT\n",
+        );
+        assert_eq!(
+            section(&content, "Usage").expect("expected section"),
+            lines("## Usage\n\nUse it.\n\n```sh\n# not a heading\n```\n\n### Details\n\nMore.")
+        );
+        assert_eq!(
+            section(&content, "License").expect("expected section"),
+            lines("## License ##\n\nMIT")
+        );
+        assert!(section(&content, "not a heading").is_none());
+    }
+
+    #[test]
+    fn section_setext() {
+        let content = lines("Title\n=====\n\nUsage\n-----\n\nUse it.\n\nLicense\n-------\n");
+        assert_eq!(
+            section(&content, "Usage").expect("expected section"),
+            lines("Usage\n-----\n\nUse it.")
+        );
+    }
+
+    #[test]
+    fn demote_headings() {
+        let content = lines("Usage\n-----\n\n# Details #\n\n```sh\n# comment\n```\n\n###### Deep");
+        assert_eq!(
+            demote(&content, 1),
+            lines("### Usage\n\n## Details\n\n```sh\n# comment\n```\n\n###### Deep")
+        );
+    }
+
+    #[test]
+    fn drop_badges_lines() {
+        let content = lines(
+            "# Title\n\n[![CI](https://example.com/ci.svg)](https://example.com/ci) [![docs](https://example.com/docs.svg)](https://docs.rs)\n\nSee [![logo](logo.png)](https://example.com) here.\n\n```md\n[![CI](ci.svg)](ci)\n```",
+        );
+        assert_eq!(
+            drop_badges(&content),
+            lines("# Title\n\n\nSee  here.\n\n```md\n[![CI](ci.svg)](ci)\n```")
+        );
+    }
+
+    #[test]
+    fn rewrite_links_relative() {
+        let content = lines(
+            "See [license](LICENSE.txt \"MIT\"), [docs](https://docs.rs), and ![logo](img/logo.png).\n\n[ref]: CONTRIBUTING.md\n\n```md\n[license](LICENSE.txt)\n```",
+        );
+        let rewritten = rewrite_links(&content, |target| {
+            (!target.contains("://")).then(|| format!("https://example.com/{target}"))
+        });
+        assert_eq!(
+            rewritten,
+            lines("See [license](https://example.com/LICENSE.txt \"MIT\"), [docs](https://docs.rs), and ![logo](https://example.com/img/logo.png).\n\n[ref]: https://example.com/CONTRIBUTING.md\n\n```md\n[license](LICENSE.txt)\n```")
+        );
+    }
+}
@@ -0,0 +1,642 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{convert, Block};
+use std::{io, path::Path};
+
+/// Collects Rust source code blocks.
+pub fn collect<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    collect_lang(iter, Some("rust"))
+}
+
+/// Collects source code blocks in `lang`, or all source code blocks if `lang` is `None`.
+pub fn collect_lang<R: io::Read>(
+    iter: io::Lines<io::BufReader<R>>,
+    lang: Option<&str>,
+) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut block: Option<Block> = None;
+    let mut found_name: Option<String> = None;
+
+    for (line_idx, line) in iter.enumerate() {
+        let line = line?;
+        let line_num = (line_idx + 1) as u32;
+
+        let Some(current) = block.as_mut() else {
+            let trimmed = line.trim();
+
+            // Look for #+NAME: immediately before #+BEGIN_SRC (case-insensitive)
+            if let Some(name) = parse_name(trimmed) {
+                found_name = Some(name.to_string());
+            } else if let Some(src_lang) = parse_begin_src(trimmed)
+                .filter(|src_lang| lang.is_none_or(|lang| *src_lang == Some(lang)))
+            {
+                block = Some(Block {
+                    lang: src_lang.map(String::from),
+                    names: found_name.take().into_iter().collect(),
+                    untested: trimmed
+                        .split_whitespace()
+                        .any(|arg| arg.eq_ignore_ascii_case(":untested")),
+                    tangle: parse_tangle(trimmed).map(String::from),
+                    open_line: line_num,
+                    start_line: line_num + 1,
+                    ..Default::default()
+                });
+            } else {
+                // Reset if we see any line that's not BEGIN_SRC after finding a name
+                // This ensures NAME must be immediately before BEGIN_SRC
+                found_name = None;
+            }
+            continue;
+        };
+
+        let trimmed = line.trim();
+
+        // Check for end of block (case-insensitive)
+        if trimmed
+            .get(..9)
+            .is_some_and(|s| s.eq_ignore_ascii_case("#+END_SRC"))
+        {
+            blocks.extend(block.take());
+            continue;
+        }
+
+        // Collect the line
+        current.lines.push(line);
+    }
+
+    // An unterminated block continues to the end of the file.
+    blocks.extend(block.map(|block| Block {
+        unterminated: true,
+        ..block
+    }));
+
+    Ok(blocks)
+}
+
+/// Converts Org to Markdown, converting headlines, lists, links, emphasis, tables, and source and example blocks.
+///
+/// Comments and keywords like `#+TITLE:` are removed.
+pub fn to_markdown<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<String>> {
+    let mut output = Vec::new();
+    // The keyword that closes the current block.
+    let mut end: Option<&str> = None;
+    let mut table: Vec<Vec<String>> = Vec::new();
+
+    for line in iter {
+        let line = line?;
+        let trimmed = line.trim();
+
+        if let Some(keyword) = end {
+            if trimmed
+                .get(..keyword.len())
+                .is_some_and(|s| s.eq_ignore_ascii_case(keyword))
+            {
+                output.push("```".into());
+                end = None;
+            } else {
+                output.push(line);
+            }
+            continue;
+        }
+
+        // Table rows like `| a | b |`; horizontal rules like `|---+---|` are replaced by the header separator.
+        if let Some(row) = trimmed.strip_prefix('|') {
+            if !row.starts_with('-') {
+                let row = row.strip_suffix('|').unwrap_or(row);
+                table.push(row.split('|').map(|cell| inline(cell.trim())).collect());
+            }
+            continue;
+        }
+        if !table.is_empty() {
+            output.extend(convert::table(&table));
+            table.clear();
+        }
+
+        if let Some(lang) = parse_begin_src(trimmed) {
+            output.push(format!("```{}", lang.unwrap_or_default()));
+            end = Some("#+END_SRC");
+            continue;
+        }
+        if trimmed
+            .get(..15)
+            .is_some_and(|s| s.eq_ignore_ascii_case("#+BEGIN_EXAMPLE"))
+        {
+            output.push("```".into());
+            end = Some("#+END_EXAMPLE");
+            continue;
+        }
+        if trimmed.starts_with("#+") || trimmed == "#" || trimmed.starts_with("# ") {
+            continue;
+        }
+
+        let level = line.chars().take_while(|&c| c == '*').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            output.push(format!(
+                "{} {}",
+                "#".repeat(level.min(6)),
+                inline(line[level..].trim())
+            ));
+            continue;
+        }
+
+        // Lists like `+ item` or `1) item` become `- item` or `1. item`.
+        let indent = &line[..line.len() - line.trim_start().len()];
+        if let Some(item) = trimmed.strip_prefix("+ ") {
+            output.push(format!("{indent}- {}", inline(item)));
+            continue;
+        }
+        let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+        if let Some(item) = (digits > 0)
+            .then(|| trimmed[digits..].strip_prefix(") "))
+            .flatten()
+        {
+            output.push(format!("{indent}{}. {}", &trimmed[..digits], inline(item)));
+            continue;
+        }
+
+        output.push(inline(&line));
+    }
+
+    if !table.is_empty() {
+        output.extend(convert::table(&table));
+    }
+    if end.is_some() {
+        output.push("```".into());
+    }
+    Ok(output)
+}
+
+/// Converts links and emphasis like `*bold*`, `/italic/`, `=verbatim=`, `~code~`, and `+strike-through+` to Markdown.
+fn inline(line: &str) -> String {
+    convert::inline(
+        &convert_links(line),
+        &[('*', "**"), ('/', "*"), ('=', "`"), ('~', "`"), ('+', "~~")],
+    )
+}
+
+/// Converts links like `[[target][text]]` or `[[target]]` to Markdown links.
+fn convert_links(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("[[") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            output.push_str("[[");
+            rest = after;
+            continue;
+        };
+        let (target, text) = match after[..end].split_once("][") {
+            Some((target, text)) => (target, text),
+            None => (&after[..end], &after[..end]),
+        };
+        let target = target.strip_prefix("file:").unwrap_or(target);
+        output.push_str(&format!("[{text}]({target})"));
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn parse_name(line: &str) -> Option<&str> {
+    // Look for #+NAME: followed by whitespace and the name (case-insensitive)
+    // Example: #+NAME: example or #+name: example
+    let trimmed = line.trim();
+    if let Some(prefix) = trimmed.get(..7) {
+        if prefix.eq_ignore_ascii_case("#+NAME:") {
+            return Some(trimmed[7..].trim_start());
+        }
+    }
+    None
+}
+
+fn parse_begin_src(line: &str) -> Option<Option<&str>> {
+    // Check if the line is #+BEGIN_SRC followed by an optional language (case-insensitive)
+    // Example: #+BEGIN_SRC rust or #+begin_src rust
+    let trimmed = line.trim();
+    let prefix = trimmed.get(..11)?;
+    if !prefix.eq_ignore_ascii_case("#+BEGIN_SRC") {
+        return None;
+    }
+    let rest = &trimmed[11..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    // The language is the first argument unless it's a header argument like :exports
+    Some(
+        rest.split_whitespace()
+            .next()
+            .filter(|arg| !arg.starts_with(':')),
+    )
+}
+
+fn parse_tangle(line: &str) -> Option<&str> {
+    // Look for the :tangle header argument followed by a path or `yes`, ignoring `no`
+    // Example: #+BEGIN_SRC rust :tangle src/example.rs
+    let mut args = line.split_whitespace();
+    args.find(|arg| arg.eq_ignore_ascii_case(":tangle"))?;
+    args.next()
+        .filter(|path| !path.starts_with(':') && *path != "no")
+}
+
+/// Returns the target of a `:tangle yes` block in `lang` within the Org file at `path`.
+///
+/// Like Emacs, this is the name of the Org file with an extension for the language e.g., `rs` for `rust`.
+pub(crate) fn tangle_target(path: &Path, lang: Option<&str>) -> io::Result<String> {
+    let lang = lang.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            ":tangle yes requires a language to name the target file",
+        )
+    })?;
+    let ext = match lang {
+        "rust" => "rs",
+        "python" => "py",
+        "shell" | "sh" | "bash" => "sh",
+        "javascript" | "js" => "js",
+        "emacs-lisp" | "elisp" => "el",
+        _ => lang,
+    };
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    Ok(format!("{stem}.{ext}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, collect_lang, tangle_target, to_markdown};
+    use crate::{extract, Selector};
+    use std::io::{self, BufRead};
+
+    #[test]
+    fn extract_no_code_blocks() {
+        let content = r#"This is an Org file
+with no code blocks at all.
+Just plain text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_no_matching_name() {
+        let content = r#"Some text here.
+
+#+NAME: other
+#+BEGIN_SRC rust
+fn main() {
+    println!("Hello");
+}
+#+END_SRC
+
+More text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_basic_block() {
+        let content = r#"Some introduction text.
+
+#+NAME: example
+#+BEGIN_SRC rust
+println!("hello, world!");
+#+END_SRC
+
+Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("hello, world!");"#);
+    }
+
+    #[test]
+    fn extract_multiline_block() {
+        let content = r#"Some introduction text.
+
+#+NAME: example
+#+BEGIN_SRC rust
+fn test() {
+    assert_eq!(2 + 2, 4);
+}
+#+END_SRC
+
+Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    assert_eq!(2 + 2, 4);
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_multiple_blocks_one_match() {
+        let content = r#"Here's the first block:
+
+#+NAME: other
+#+BEGIN_SRC python
+print("Not this one")
+#+END_SRC
+
+And here's the one we want:
+
+#+NAME: example
+#+BEGIN_SRC rust
+println!("This is the one!");
+#+END_SRC
+
+And another one."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("This is the one!");"#);
+    }
+
+    #[test]
+    fn extract_with_indentation() {
+        let content = r#"Text before.
+
+#+NAME: example
+#+BEGIN_SRC rust
+    let indented = "value";
+    println!("{}", indented);
+#+END_SRC
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"    let indented = "value";
+    println!("{}", indented);"#
+        );
+    }
+
+    #[test]
+    fn extract_empty_lines_within_block() {
+        let content = r#"Text before.
+
+#+NAME: example
+#+BEGIN_SRC rust
+fn first() {}
+
+fn second() {}
+#+END_SRC
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn first() {}
+
+fn second() {}"#
+        );
+    }
+
+    #[test]
+    fn extract_until_eof() {
+        let content = r#"Text before.
+
+#+NAME: example
+#+BEGIN_SRC rust
+struct Point {
+    x: i32,
+    y: i32,
+}"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"struct Point {
+    x: i32,
+    y: i32,
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_different_language() {
+        let content = r#"Text before.
+
+#+NAME: example
+#+BEGIN_SRC python
+print("hello")
+#+END_SRC
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_name_without_begin_src() {
+        let content = r#"Text before.
+
+#+NAME: example
+Some other text here.
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_name_not_immediately_before() {
+        let content = r#"Text before.
+
+#+NAME: example
+
+#+BEGIN_SRC rust
+println!("hello");
+#+END_SRC
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_lowercase_directives() {
+        let content = r#"Text before.
+
+#+name: example
+#+begin_src rust
+println!("lowercase directives");
+#+end_src
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("lowercase directives");"#);
+    }
+
+    #[test]
+    fn extract_mixed_case_directives() {
+        let content = r#"Text before.
+
+#+Name: example
+#+Begin_Src rust
+println!("mixed case");
+#+End_Src
+
+Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("mixed case");"#);
+    }
+
+    #[test]
+    fn extract_start_line() {
+        // #+NAME on line 3, #+BEGIN_SRC on line 4, first content on line 5.
+        let content = "Text.\n\n#+NAME: example\n#+BEGIN_SRC rust\nprintln!(\"hi\");\n#+END_SRC\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 5);
+    }
+
+    #[test]
+    fn collect_open_line() {
+        let content = "Text.\n\n#+NAME: example\n#+BEGIN_SRC rust\nprintln!(\"hi\");\n#+END_SRC\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].open_line, 4);
+    }
+
+    #[test]
+    fn extract_index() {
+        let content = "#+NAME: first\n#+BEGIN_SRC rust\nlet x = 1;\n#+END_SRC\n\n#+BEGIN_SRC rust\nlet y = 2;\n#+END_SRC\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, result) =
+            extract(cursor, Selector::Index(2), collect).expect("expected content");
+        assert_eq!(start_line, 7);
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn extract_lines() {
+        let content = "#+BEGIN_SRC rust\nlet x = 1;\nlet y = 2;\n#+END_SRC\n";
+        let cursor = io::Cursor::new(content);
+        let (_, result) =
+            extract(cursor, Selector::Lines(3..4), collect).expect("expected content");
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn collect_untested() {
+        let content = "#+NAME: example\n#+BEGIN_SRC rust :untested\nlet x = 1;\n#+END_SRC\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].name(), Some("example"));
+        assert!(blocks[0].untested);
+    }
+
+    #[test]
+    fn collect_tangle() {
+        let content = "#+BEGIN_SRC rust :tangle src/example.rs :exports code\nlet x = 1;\n#+END_SRC\n\n#+BEGIN_SRC rust :tangle no\nlet y = 2;\n#+END_SRC\n\n#+BEGIN_SRC rust :tangle yes\nlet z = 3;\n#+END_SRC\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].tangle.as_deref(), Some("src/example.rs"));
+        assert_eq!(blocks[1].tangle, None);
+        assert_eq!(blocks[2].tangle.as_deref(), Some("yes"));
+    }
+
+    #[test]
+    fn tangle_target_yes() {
+        let path = std::path::Path::new("docs/design.org");
+        assert_eq!(
+            tangle_target(path, Some("rust")).expect("expected target"),
+            "design.rs"
+        );
+        assert_eq!(
+            tangle_target(path, Some("toml")).expect("expected target"),
+            "design.toml"
+        );
+        let err = tangle_target(path, None).expect_err("expected missing language");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn collect_lang_any() {
+        let content = "#+BEGIN_SRC sh :exports code\ncargo test\n#+END_SRC\n\n#+BEGIN_SRC\nplain\n#+END_SRC\n\n#+NAME: example\n#+BEGIN_SRC rust\nlet x = 1;\n";
+        let blocks = collect_lang(io::BufReader::new(io::Cursor::new(content)).lines(), None)
+            .expect("expected blocks");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].lang.as_deref(), Some("sh"));
+        assert_eq!(blocks[1].lang, None);
+        assert_eq!(blocks[2].lang.as_deref(), Some("rust"));
+        assert_eq!(blocks[2].name(), Some("example"));
+        assert!(!blocks[1].unterminated);
+        assert!(blocks[2].unterminated);
+    }
+
+    #[test]
+    fn to_markdown_sections() {
+        let content = r#"#+TITLE: Example
+# A comment.
+* Usage
+
+See [[file:../LICENSE.txt][MIT]] or [[https://docs.rs]].
+
+#+NAME: example
+#+BEGIN_SRC rust
+# not a comment
+let x = 1;
+#+END_SRC
+
+** Details
+#+begin_example
+literal
+#+end_example
+"#;
+        let actual = to_markdown(io::BufReader::new(io::Cursor::new(content)).lines())
+            .expect("expected Markdown");
+        assert_eq!(
+            actual.join("\n"),
+            r#"# Usage
+
+See [MIT](../LICENSE.txt) or [https://docs.rs](https://docs.rs).
+
+```rust
+# not a comment
+let x = 1;
+```
+
+## Details
+```
+literal
+```"#
+        );
+    }
+
+    #[test]
+    fn to_markdown_prose() {
+        let content = r#"* Lists
+- *Bold* and /italic/ with =code= and ~code~
+  + Nested
+1) First
+
+| Name  | Description |
+|-------+-------------|
+| scope | +Removed+   |
+"#;
+        let actual = to_markdown(io::BufReader::new(io::Cursor::new(content)).lines())
+            .expect("expected Markdown");
+        assert_eq!(
+            actual.join("\n"),
+            r#"# Lists
+- **Bold** and *italic* with `code` and `code`
+  - Nested
+1. First
+
+| Name | Description |
+| --- | --- |
+| scope | ~~Removed~~ |"#
+        );
+    }
+}
@@ -0,0 +1,830 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+// cspell:ignore notextile peekable myclass
+
+use super::{convert, Block};
+use std::io;
+
+/// Collects Rust block code.
+pub fn collect<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    collect_lang(iter, Some("rust"))
+}
+
+/// Collects block code in `lang`, or all block code if `lang` is `None`.
+pub fn collect_lang<R: io::Read>(
+    iter: io::Lines<io::BufReader<R>>,
+    lang: Option<&str>,
+) -> io::Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut block: Option<Block> = None;
+    let mut is_double_period = false;
+
+    for (line_idx, line) in iter.enumerate() {
+        let line = line?;
+        let line_num = (line_idx + 1) as u32;
+
+        let Some(current) = block.as_mut() else {
+            // Look for a code block starting with bc(rust#name). or bc(rust#name)..
+            // or bc[rust](#name). or bc(#name)[rust].
+            let trimmed = line.trim();
+
+            if let Some(signature) = parse_signature(trimmed)
+                .filter(|signature| lang.is_none_or(|lang| signature.lang == Some(lang)))
+            {
+                is_double_period = signature.double_period;
+
+                // Code MUST start on the same line in Textile
+                if !signature.content.is_empty() {
+                    block = Some(Block {
+                        lang: signature.lang.map(String::from),
+                        names: signature.id.map(String::from).into_iter().collect(),
+                        // content starts on this same line, after the bc directive
+                        open_line: line_num,
+                        start_line: line_num,
+                        lines: vec![signature.content.to_string()],
+                        untested: signature.untested,
+                        ..Default::default()
+                    });
+                }
+            }
+            continue;
+        };
+
+        let trimmed = line.trim();
+
+        if is_double_period {
+            // Double period: collect until next block command (text followed by .)
+            if is_block_tag(trimmed) {
+                // Remove trailing empty lines before the block tag
+                while let Some(last) = current.lines.last() {
+                    if last.trim().is_empty() {
+                        current.lines.pop();
+                    } else {
+                        break;
+                    }
+                }
+                blocks.extend(block.take());
+                continue;
+            }
+        } else if trimmed.is_empty() {
+            // Single period: collect until first blank line
+            blocks.extend(block.take());
+            continue;
+        }
+
+        // Collect the line
+        current.lines.push(line);
+    }
+
+    // A block continues to the end of the file.
+    blocks.extend(block);
+
+    Ok(blocks)
+}
+
+/// Converts Textile to Markdown, converting headings, lists, links, emphasis, tables, and code and preformatted blocks.
+pub fn to_markdown<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<String>> {
+    let mut output = Vec::new();
+    // Whether the current code block ends at the next block tag instead of a blank line.
+    let mut block: Option<bool> = None;
+    let mut table: Vec<Vec<String>> = Vec::new();
+
+    for line in iter {
+        let line = line?;
+        let trimmed = line.trim();
+
+        if let Some(double_period) = block {
+            if double_period && is_block_tag(trimmed) {
+                // Remove trailing empty lines before the block tag.
+                let blank = output
+                    .iter()
+                    .rev()
+                    .take_while(|line: &&String| line.trim().is_empty())
+                    .count();
+                output.truncate(output.len() - blank);
+                output.push("```".into());
+                output.extend(std::iter::repeat_n(String::new(), blank));
+                block = None;
+            } else if !double_period && trimmed.is_empty() {
+                output.extend(["```".to_string(), line]);
+                block = None;
+                continue;
+            } else {
+                output.push(line);
+                continue;
+            }
+        }
+
+        // Table rows like `|_. a |_. b |` or `| a | b |`.
+        if let Some(row) = trimmed
+            .strip_prefix('|')
+            .and_then(|row| row.strip_suffix('|'))
+        {
+            table.push(
+                row.split('|')
+                    .map(|cell| {
+                        let cell = cell.trim();
+                        inline(cell.strip_prefix("_.").unwrap_or(cell).trim())
+                    })
+                    .collect(),
+            );
+            continue;
+        }
+        if !table.is_empty() {
+            output.extend(convert::table(&table));
+            table.clear();
+        }
+
+        if let Some(signature) = parse_signature(trimmed) {
+            output.extend([
+                format!("```{}", signature.lang.unwrap_or_default()),
+                signature.content.to_string(),
+            ]);
+            block = Some(signature.double_period);
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix("pre.") {
+            let (double_period, content) = match rest.strip_prefix('.') {
+                Some(rest) => (true, rest),
+                None => (false, rest),
+            };
+            output.extend(["```".to_string(), content.trim_start().to_string()]);
+            block = Some(double_period);
+            continue;
+        }
+        if trimmed.starts_with("table") && is_block_tag(trimmed) {
+            continue;
+        }
+
+        if let Some((tag, text)) = trimmed
+            .split_once(". ")
+            .filter(|(tag, _)| is_block_tag(&format!("{tag}.")))
+        {
+            let text = inline(text);
+            let name = tag
+                .split(['(', '[', '{', '<', '>', '='])
+                .next()
+                .unwrap_or(tag);
+            match name {
+                "p" => output.push(text),
+                "bq" => output.push(format!("> {text}")),
+                _ => match name
+                    .strip_prefix('h')
+                    .and_then(|level| level.parse::<usize>().ok())
+                {
+                    Some(level) => output.push(format!("{} {text}", "#".repeat(level))),
+                    None => output.push(text),
+                },
+            }
+            continue;
+        }
+
+        // Lists like `* item`, `** nested`, or `# item`.
+        let marker = trimmed.chars().next().filter(|c| *c == '*' || *c == '#');
+        let depth = marker.map_or(0, |marker| {
+            trimmed.chars().take_while(|&c| c == marker).count()
+        });
+        if let Some(item) = marker.and_then(|_| trimmed[depth..].strip_prefix(' ')) {
+            let bullet = if marker == Some('*') { "-" } else { "1." };
+            output.push(format!(
+                "{}{bullet} {}",
+                "   ".repeat(depth - 1),
+                inline(item.trim())
+            ));
+            continue;
+        }
+
+        output.push(inline(&line));
+    }
+
+    if !table.is_empty() {
+        output.extend(convert::table(&table));
+    }
+    if block.is_some() {
+        output.push("```".into());
+    }
+    Ok(output)
+}
+
+/// Converts links and emphasis like `*strong*`, `_emphasis_`, and `@code@` to Markdown.
+fn inline(line: &str) -> String {
+    convert::inline(&convert_links(line), &[('*', "**"), ('_', "*"), ('@', "`")])
+}
+
+/// Converts links like `"text":https://example.com` to Markdown links.
+fn convert_links(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("\":") {
+        let Some(open) = rest[..start].rfind('"') else {
+            output.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        };
+        let text = &rest[open + 1..start];
+        let after = &rest[start + 2..];
+        let len = after.find(char::is_whitespace).unwrap_or(after.len());
+        // Trailing punctuation is not part of the URL.
+        let target = after[..len].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+        output.push_str(&rest[..open]);
+        output.push_str(&format!("[{text}]({target})"));
+        rest = &after[target.len()..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// The signature of a code block e.g., `bc(rust#example). content`.
+struct Signature<'a> {
+    lang: Option<&'a str>,
+    id: Option<&'a str>,
+    double_period: bool,
+    untested: bool,
+    content: &'a str,
+}
+
+fn parse_signature(line: &str) -> Option<Signature<'_>> {
+    // Parse bc(rust#name). or bc(rust#name).. or bc[rust](#name). or bc(#name)[rust].
+    // Classes are separated by spaces e.g., bc(rust untested#name). to opt out of coverage checks.
+    // Examples: bc(rust#example).
+    //           bc(rust#example)..
+    //           bc[rust](#example).
+    //           bc(#example)[rust]..
+    let mut rest = line.strip_prefix("bc")?;
+    let mut lang = None;
+    let mut classes = "";
+    let mut id = None;
+
+    loop {
+        if let Some(after) = rest.strip_prefix('(') {
+            // Classes and optional id e.g., (rust#example) or (#example)
+            let end = after.find(')')?;
+            match after[..end].split_once('#') {
+                Some((class, name)) => {
+                    classes = class;
+                    id = Some(name);
+                }
+                None => classes = &after[..end],
+            }
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            // Language e.g., [rust]
+            let end = after.find(']')?;
+            lang = Some(&after[..end]);
+            rest = &after[end + 1..];
+        } else {
+            break;
+        }
+    }
+
+    // Check if followed by . or .. and the content after a space
+    let rest = rest.strip_prefix('.')?;
+    let (double_period, rest) = match rest.strip_prefix('.') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let content = rest.strip_prefix(' ')?;
+
+    let mut classes = classes.split_whitespace();
+    Some(Signature {
+        lang: lang.or_else(|| classes.next()),
+        id,
+        double_period,
+        untested: classes.any(|class| class == "untested"),
+        content,
+    })
+}
+
+fn is_block_tag(line: &str) -> bool {
+    // Check if line starts a new textile block
+    // Block tags can have formatting characters like: p<., h1>., table(class)., etc.
+    // Valid formatting chars before period: <, >, =, and content in () or []
+    if line.is_empty() {
+        return false;
+    }
+
+    // Known block types
+    let block_types = [
+        "p",
+        "bq",
+        "bc",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "table",
+        "pre",
+        "notextile",
+    ];
+
+    for block_type in &block_types {
+        if let Some(after_block) = line.strip_prefix(block_type) {
+            // Block tag must be followed by valid formatting chars and then a period
+            // Valid chars: <, >, =, and content within () or []
+            let mut chars = after_block.chars().peekable();
+            let mut found_period = false;
+
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '.' => {
+                        found_period = true;
+                        break;
+                    }
+                    '<' | '>' | '=' => {
+                        // Valid alignment/formatting character, continue
+                    }
+                    '(' => {
+                        // Parentheses can be:
+                        // 1. Empty or for indentation: p(. or p((.
+                        // 2. With content: table(myclass).
+                        // Look ahead to check if there's content before ) or .
+                        let mut depth = 1;
+                        while let Some(&next_ch) = chars.peek() {
+                            if next_ch == '.' {
+                                // Period found, this paren is just formatting
+                                break;
+                            } else if next_ch == ')' {
+                                chars.next(); // consume )
+                                depth -= 1;
+                                if depth == 0 {
+                                    break;
+                                }
+                            } else if next_ch == '(' {
+                                chars.next(); // consume (
+                                depth += 1;
+                            } else {
+                                chars.next(); // consume content character
+                            }
+                        }
+                    }
+                    ')' => {
+                        // Closing paren for indentation styling
+                    }
+                    '[' => {
+                        // Consume everything until matching ]
+                        for inner_ch in chars.by_ref() {
+                            if inner_ch == ']' {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {
+                        // Invalid character before period
+                        break;
+                    }
+                }
+            }
+
+            if found_period {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, collect_lang, to_markdown};
+    use crate::{extract, Selector};
+    use std::io::{self, BufRead};
+
+    #[test]
+    fn extract_no_code_blocks() {
+        let content = r#"This is a Textile file
+with no code blocks at all.
+Just plain text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_no_matching_id() {
+        let content = r#"Some text here.
+
+bc(rust#other). fn main() {
+    println!("Hello");
+}
+
+p. More text."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_single_period_with_content() {
+        let content = r#"Some introduction text.
+
+bc[rust](#example). println!("hello, world!");
+
+p. Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("hello, world!");"#);
+    }
+    #[test]
+    fn extract_double_period_multiline() {
+        let content = r#"Some introduction text.
+
+bc(#example)[rust].. fn test() {
+    assert_eq!(2 + 2, 4);
+}
+
+p. Text after the block."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    assert_eq!(2 + 2, 4);
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_multiline_until_next_block() {
+        let content = r#"Some introduction text.
+
+bc[rust](#example).. let x = 42;
+let y = x + 1;
+
+bq. This is a quote block that ends the code."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let x = 42;
+let y = x + 1;"#
+        );
+    }
+
+    #[test]
+    fn extract_multiple_blocks_one_match() {
+        let content = r#"Here's the first block:
+
+bc(python#other). print("Not this one")
+
+p. And here's the one we want:
+
+bc(#example)[rust]. println!("This is the one!");
+
+p. And another one."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, r#"println!("This is the one!");"#);
+    }
+
+    #[test]
+    fn extract_content_on_next_line() {
+        let content = r#"Text before.
+
+bc(rust#example).. fn with_content() {
+    println!("On next line");
+}
+
+p. Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn with_content() {
+    println!("On next line");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_with_class_before_id() {
+        let content = r#"Text before.
+
+bc[rust](#example). let value = 123;
+
+p. Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, "let value = 123;");
+    }
+
+    #[test]
+    fn extract_until_eof() {
+        let content = r#"Text before.
+
+bc(rust#example).. struct Point {
+    x: i32,
+    y: i32,
+}"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"struct Point {
+    x: i32,
+    y: i32,
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_empty_lines_within_block() {
+        let content = r#"Text before.
+
+bc(rust#example).. fn first() {}
+
+fn second() {}
+
+p. Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn first() {}
+
+fn second() {}"#
+        );
+    }
+
+    #[test]
+    fn extract_different_language() {
+        let content = r#"Text before.
+
+bc(python#example). print("hello")
+
+p. Text after."#;
+        let cursor = io::Cursor::new(content);
+        let result = extract(cursor, "example", collect);
+        assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn extract_ends_at_right_aligned_header() {
+        let content = r#"Text before.
+
+bc(rust#example).. let x = 1;
+let y = 2;
+
+h1>. Right Aligned Header"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let x = 1;
+let y = 2;"#
+        );
+    }
+
+    #[test]
+    fn extract_ends_at_justified_paragraph() {
+        let content = r#"Text before.
+
+bc(rust#example).. fn test() {
+    println!("test");
+}
+
+p<>. Justified paragraph text."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    println!("test");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_ends_at_centered_header() {
+        let content = r#"Text before.
+
+bc(rust#example).. let value = 42;
+
+h2=. Centered Header"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, "let value = 42;");
+    }
+
+    #[test]
+    fn extract_ends_at_indented_paragraph() {
+        let content = r#"Text before.
+
+bc(rust#example).. struct Point {
+    x: i32,
+}
+
+p(. Indented paragraph."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"struct Point {
+    x: i32,
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_ends_at_table_with_class() {
+        let content = r#"Text before.
+
+bc(rust#example).. let data = vec![1, 2, 3];
+
+table(myclass). Some table content"#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, "let data = vec![1, 2, 3];");
+    }
+
+    #[test]
+    fn extract_ends_at_notextile_block() {
+        let content = r#"Text before.
+
+bc(rust#example).. fn example() {
+    println!("code");
+}
+
+notextile. Raw content here."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn example() {
+    println!("code");
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_bc_with_bracket_syntax() {
+        let content = r#"Text before.
+
+bc[rust](#example). let value = 100;
+
+p. Text after."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(result, "let value = 100;");
+    }
+
+    #[test]
+    fn extract_ends_at_right_padded_paragraph() {
+        let content = r#"Text before.
+
+bc(rust#example).. let x = 10;
+let y = 20;
+
+p))). Right padded paragraph."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"let x = 10;
+let y = 20;"#
+        );
+    }
+
+    #[test]
+    fn extract_ends_at_combined_padding_paragraph() {
+        let content = r#"Text before.
+
+bc(rust#example).. fn test() {
+    return 42;
+}
+
+p()). Left indent and right padding."#;
+        let cursor = io::Cursor::new(content);
+        let (_, result) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(
+            result,
+            r#"fn test() {
+    return 42;
+}"#
+        );
+    }
+
+    #[test]
+    fn extract_start_line() {
+        // bc tag is on line 3; content starts on the same line.
+        let content = "Text.\n\nbc(rust#example). let x = 1;\n";
+        let cursor = io::Cursor::new(content);
+        let (start_line, _) = extract(cursor, "example", collect).expect("expected content");
+        assert_eq!(start_line, 3);
+    }
+
+    #[test]
+    fn collect_open_line() {
+        // Content starts on the same line as the bc tag.
+        let content = "Text.\n\nbc(rust#example). let x = 1;\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks[0].open_line, 3);
+        assert_eq!(blocks[0].start_line, 3);
+    }
+
+    #[test]
+    fn extract_index() {
+        let content = "bc(rust#first). let x = 1;\n\nbc[rust]. let y = 2;\n\np. Text after.";
+        let cursor = io::Cursor::new(content);
+        let (start_line, result) =
+            extract(cursor, Selector::Index(2), collect).expect("expected content");
+        assert_eq!(start_line, 3);
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn extract_lines() {
+        let content = "bc[rust].. let x = 1;\nlet y = 2;\n\np. Text after.";
+        let cursor = io::Cursor::new(content);
+        let (_, result) =
+            extract(cursor, Selector::Lines(2..3), collect).expect("expected content");
+        assert_eq!(result, "let y = 2;");
+    }
+
+    #[test]
+    fn collect_untested() {
+        let content = "bc(rust untested#example). let x = 1;\n\nbc[rust](untested). let y = 2;\n";
+        let blocks =
+            collect(io::BufReader::new(io::Cursor::new(content)).lines()).expect("expected blocks");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].name(), Some("example"));
+        assert_eq!(blocks[0].lines, vec!["let x = 1;"]);
+        assert!(blocks[0].untested);
+        assert!(blocks[1].untested);
+    }
+
+    #[test]
+    fn collect_lang_any() {
+        let content = "bc(sh). cargo test\n\nbc. plain\n\nbc[rust](#example). let x = 1;\n";
+        let blocks = collect_lang(io::BufReader::new(io::Cursor::new(content)).lines(), None)
+            .expect("expected blocks");
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].lang.as_deref(), Some("sh"));
+        assert_eq!(blocks[1].lang, None);
+        assert_eq!(blocks[2].lang.as_deref(), Some("rust"));
+        assert_eq!(blocks[2].name(), Some("example"));
+        assert!(!blocks[2].unterminated);
+    }
+
+    #[test]
+    fn to_markdown_prose() {
+        let content = r#"h1. Title
+
+p. See "the docs":https://docs.rs. and *strong* _emphasis_ @code@.
+
+* Item
+** Nested
+# First
+
+bc(rust#example). let x = 1;
+let y = 2;
+
+bc.. fn main() {}
+
+fn other() {}
+
+h2(#usage). Usage
+
+table(options).
+|_. Name |_. Description |
+| scope | Include in braces. |
+
+bq. Quoted.
+"#;
+        let actual = to_markdown(io::BufReader::new(io::Cursor::new(content)).lines())
+            .expect("expected Markdown");
+        assert_eq!(
+            actual.join("\n"),
+            r#"# Title
+
+See [the docs](https://docs.rs). and **strong** *emphasis* `code`.
+
+- Item
+   - Nested
+1. First
+
+```rust
+let x = 1;
+let y = 2;
+```
+
+```
+fn main() {}
+
+fn other() {}
+```
+
+## Usage
+
+| Name | Description |
+| --- | --- |
+| scope | Include in braces. |
+
+> Quoted."#
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::Config;
+use include_file_core::Block;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::SystemTime,
+};
+
+/// A parsed file, valid while the file's modification time and size are unchanged.
+struct Entry<T> {
+    modified: Option<SystemTime>,
+    len: u64,
+    value: Arc<T>,
+}
+
+impl<T> Entry<T> {
+    fn is_valid(&self, modified: Option<SystemTime>, len: u64) -> bool {
+        self.modified.is_some() && self.modified == modified && self.len == len
+    }
+}
+
+/// Entries keyed by `K` shared by every macro expansion.
+type Cache<K, T> = OnceLock<Mutex<HashMap<K, Entry<T>>>>;
+
+/// Files parsed during this compiler session keyed by canonical path and collector type.
+///
+/// Proc-macros are loaded once per crate compilation - or for much longer by rust-analyzer -
+/// so each file is parsed once no matter how many macros include it, and again only if it changed.
+static CACHE: Cache<(PathBuf, TypeId), Vec<Block>> = OnceLock::new();
+
+/// Crate configurations loaded during this compiler session keyed by the path of the `Cargo.toml`.
+static CONFIGS: Cache<PathBuf, Config> = OnceLock::new();
+
+/// Returns the blocks collected from `file` at canonical `path` by `f`, parsing the file only if not already cached.
+pub fn blocks<F>(file: fs::File, path: &Path, f: F) -> io::Result<Arc<Vec<Block>>>
+where
+    F: 'static + FnOnce(io::Lines<io::BufReader<fs::File>>) -> io::Result<Vec<Block>>,
+{
+    let metadata = file.metadata()?;
+    let modified = metadata.modified().ok();
+    let len = metadata.len();
+    let key = (path.to_path_buf(), TypeId::of::<F>());
+
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(entry) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        if entry.is_valid(modified, len) {
+            return Ok(entry.value.clone());
+        }
+    }
+
+    // Parse outside the lock so other files can be parsed concurrently.
+    let blocks = Arc::new(f(io::BufReader::new(file).lines())?);
+    cache.lock().unwrap_or_else(PoisonError::into_inner).insert(
+        key,
+        Entry {
+            modified,
+            len,
+            value: blocks.clone(),
+        },
+    );
+    Ok(blocks)
+}
+
+/// Returns the configuration of the crate in `manifest_dir`, loading its `Cargo.toml` only if not already cached.
+pub fn config(manifest_dir: &Path) -> io::Result<Arc<Config>> {
+    let path = manifest_dir.join("Cargo.toml");
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Arc::default()),
+        Err(err) => return Err(err),
+    };
+    let modified = metadata.modified().ok();
+    let len = metadata.len();
+
+    let configs = CONFIGS.get_or_init(Default::default);
+    if let Some(entry) = configs
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&path)
+    {
+        if entry.is_valid(modified, len) {
+            return Ok(entry.value.clone());
+        }
+    }
+
+    let config = Arc::new(Config::load(manifest_dir)?);
+    configs
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(
+            path,
+            Entry {
+                modified,
+                len,
+                value: config.clone(),
+            },
+        );
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, config};
+    use include_file_core::Block;
+    use std::{env, fs, io, path::PathBuf, sync::Arc};
+
+    fn collect<R: io::Read>(iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+        Ok(vec![Block {
+            lines: iter.collect::<io::Result<_>>()?,
+            ..Default::default()
+        }])
+    }
+
+    fn write(content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("include-file-cache-{}.md", std::process::id()));
+        fs::write(&path, content).expect("write temp file");
+        path
+    }
+
+    #[test]
+    fn blocks_cached() {
+        let path = write("cached");
+        let first = blocks(
+            fs::File::open(&path).expect("open temp file"),
+            &path,
+            collect::<fs::File>,
+        )
+        .expect("expected blocks");
+        let second = blocks(
+            fs::File::open(&path).expect("open temp file"),
+            &path,
+            collect::<fs::File>,
+        )
+        .expect("expected blocks");
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // A different collector parses the file again.
+        let other = blocks(
+            fs::File::open(&path).expect("open temp file"),
+            &path,
+            include_file_core::markdown::collect::<fs::File>,
+        )
+        .expect("expected blocks");
+        assert!(!Arc::ptr_eq(&first, &other));
+
+        // A change in size parses the file again.
+        fs::write(&path, "changed").expect("write temp file");
+        let changed = blocks(
+            fs::File::open(&path).expect("open temp file"),
+            &path,
+            collect::<fs::File>,
+        )
+        .expect("expected blocks");
+        assert_eq!(
+            changed.as_slice(),
+            [Block {
+                lines: vec!["changed".into()],
+                ..Default::default()
+            }]
+        );
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn config_cached() {
+        let dir = env::temp_dir().join(format!("include-file-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("Cargo.toml");
+        fs::write(&path, "[package.metadata.include-file]\nscope = true\n")
+            .expect("write temp file");
+
+        let first = config(&dir).expect("expected config");
+        let second = config(&dir).expect("expected config");
+        assert!(first.scope);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // A change in size loads the configuration again.
+        fs::write(&path, "[package.metadata.include-file]\nscope = false\n")
+            .expect("write temp file");
+        let changed = config(&dir).expect("expected config");
+        assert!(!changed.scope);
+
+        // A missing Cargo.toml uses the defaults.
+        fs::remove_dir_all(&dir).ok();
+        assert!(!config(&dir).expect("expected config").scope);
+    }
+}
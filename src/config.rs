@@ -0,0 +1,344 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{Mode, Root};
+use include_file_core::Format;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The default maximum size of an included file in strict mode.
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Settings from the `[package.metadata.include-file]` table in the calling crate's `Cargo.toml`.
+///
+/// Parameters passed to a macro override these defaults.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The default directory paths are relative to.
+    pub root: Root,
+    /// The default mode to include snippets.
+    pub mode: Mode,
+    /// Whether to include snippets in braces `{ .. }` by default.
+    pub scope: bool,
+    /// Formats for file extensions, overriding the default format for an extension.
+    pub extensions: HashMap<String, Format>,
+    /// Reject files outside the package or workspace, or larger than `max_file_size`.
+    /// Always enabled by the `strict` feature.
+    pub strict: bool,
+    /// The maximum size of an included file in bytes when `strict`.
+    pub max_file_size: u64,
+    /// The name of the package's library crate, which rustdoc brings into scope for doctests.
+    pub lib: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            root: Root::default(),
+            mode: Mode::default(),
+            scope: false,
+            extensions: HashMap::new(),
+            strict: cfg!(feature = "strict"),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            lib: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads settings from the `Cargo.toml` in `manifest_dir`.
+    pub fn load(manifest_dir: &Path) -> io::Result<Self> {
+        match fs::read_to_string(manifest_dir.join("Cargo.toml")) {
+            Ok(content) => content.parse(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the format of a file based on its extension.
+    pub fn format(&self, path: &str) -> io::Result<Format> {
+        let ext = Path::new(path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        match ext.and_then(|ext| self.extensions.get(&ext)) {
+            Some(format) => Ok(*format),
+            None => Format::from_path(path),
+        }
+    }
+
+    /// Checks that the canonical `path` of a file `len` bytes long is within one of the `allowed` directories when `strict`.
+    ///
+    /// Because `path` is canonical, this also rejects symlinks that point outside the `allowed` directories.
+    pub fn check(&self, path: &Path, len: u64, allowed: &[PathBuf]) -> io::Result<()> {
+        if !self.strict {
+            return Ok(());
+        }
+        if !allowed.iter().any(|dir| path.starts_with(dir)) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is outside the package or workspace directory",
+                    path.display()
+                ),
+            ));
+        }
+        if len > self.max_file_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is {len} bytes, larger than the maximum of {} bytes",
+                    path.display(),
+                    self.max_file_size
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Config {
+    type Err = io::Error;
+
+    /// Parses settings from the content of a `Cargo.toml`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let manifest: toml::Table = s
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut config = Self {
+            lib: manifest
+                .get("lib")
+                .and_then(|lib| lib.get("name"))
+                .or_else(|| manifest.get("package")?.get("name"))
+                .and_then(toml::Value::as_str)
+                .map(|name| name.replace('-', "_")),
+            ..Self::default()
+        };
+        let Some(table) = manifest
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("include-file"))
+        else {
+            return Ok(config);
+        };
+        let table = table
+            .as_table()
+            .ok_or_else(|| invalid("[package.metadata.include-file] must be a table".into()))?;
+
+        for (key, value) in table {
+            match key.as_str() {
+                "root" => {
+                    config.root = match value {
+                        toml::Value::String(name) => Root::from_name(name),
+                        toml::Value::Table(table) => table
+                            .get("env")
+                            .and_then(toml::Value::as_str)
+                            .filter(|_| table.len() == 1)
+                            .map(|var| Root::Env(var.into())),
+                        _ => None,
+                    }
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            r#"{key} must be "package", "workspace", "out_dir", or {{ env = "VAR" }}"#
+                        ))
+                    })?;
+                }
+                "mode" => {
+                    config.mode = match value.as_str() {
+                        Some("statements") => Mode::Statements,
+                        Some("items") => Mode::Items,
+                        Some("expr") => Mode::Expr,
+                        Some("doctest") => Mode::Doctest,
+                        _ => {
+                            return Err(invalid(format!(
+                                r#"{key} must be "statements", "items", "expr", or "doctest""#
+                            )))
+                        }
+                    };
+                }
+                "scope" => {
+                    config.scope = value
+                        .as_bool()
+                        .ok_or_else(|| invalid(format!("{key} must be a boolean")))?;
+                }
+                "extensions" => {
+                    let table = value
+                        .as_table()
+                        .ok_or_else(|| invalid(format!("{key} must be a table")))?;
+                    for (ext, format) in table {
+                        let format = format
+                            .as_str()
+                            .ok_or_else(|| invalid(format!("{key}.{ext} must be a string")))?
+                            .parse()
+                            .map_err(|err| invalid(format!("{key}.{ext}: {err}")))?;
+                        config.extensions.insert(ext.to_ascii_lowercase(), format);
+                    }
+                }
+                "strict" => {
+                    config.strict |= value
+                        .as_bool()
+                        .ok_or_else(|| invalid(format!("{key} must be a boolean")))?;
+                }
+                "max-file-size" => {
+                    config.max_file_size = value
+                        .as_integer()
+                        .and_then(|size| u64::try_from(size).ok())
+                        .ok_or_else(|| invalid(format!("{key} must be a positive integer")))?;
+                }
+                _ => return Err(invalid(format!("unsupported key {key}"))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid [package.metadata.include-file] in Cargo.toml: {message}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, DEFAULT_MAX_FILE_SIZE};
+    use crate::{Mode, Root};
+    use include_file_core::Format;
+    use std::{io, path::PathBuf};
+
+    #[test]
+    fn parse_without_metadata() {
+        let config: Config = "[package]\nname = \"example-crate\"\n"
+            .parse()
+            .expect("expected config");
+        assert_eq!(
+            config,
+            Config {
+                lib: Some("example_crate".into()),
+                ..Default::default()
+            }
+        );
+
+        let config: Config = "[package]\nname = \"example\"\n\n[lib]\nname = \"other\"\n"
+            .parse()
+            .expect("expected config");
+        assert_eq!(config.lib.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn parse_strict() {
+        let config: Config = r#"
+[package]
+name = "example"
+
+[package.metadata.include-file]
+strict = true
+max-file-size = 1024
+"#
+        .parse()
+        .expect("expected config");
+        assert!(config.strict);
+        assert_eq!(config.max_file_size, 1024);
+    }
+
+    #[test]
+    fn parse_defaults() {
+        let config: Config = r#"
+[package.metadata.include-file]
+root = { env = "DOCS_DIR" }
+mode = "expr"
+scope = true
+extensions = { mdx = "markdown", TXT = "markdown" }
+"#
+        .parse()
+        .expect("expected config");
+        assert_eq!(config.root, Root::Env("DOCS_DIR".into()));
+        assert_eq!(config.mode, Mode::Expr);
+        assert!(config.scope);
+        assert_eq!(
+            config.format("docs/intro.mdx").expect("expected format"),
+            Format::Markdown
+        );
+        assert_eq!(
+            config.format("notes.txt").expect("expected format"),
+            Format::Markdown
+        );
+        assert_eq!(
+            config.format("README.md").expect("expected format"),
+            Format::Markdown
+        );
+        assert!(config.format("notes.rst").is_err());
+
+        let config: Config = "[package.metadata.include-file]\nroot = \"workspace\"\n"
+            .parse()
+            .expect("expected config");
+        assert_eq!(config.root, Root::Workspace);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for content in [
+            "[package.metadata.include-file]\nroot = \"docs\"\n",
+            "[package.metadata.include-file]\nroot = { env = \"DOCS_DIR\", other = 1 }\n",
+            "[package.metadata.include-file]\nmode = \"scope\"\n",
+            "[package.metadata.include-file]\nextensions = { mdx = \"rst\" }\n",
+            "[package.metadata]\ninclude-file = true\n",
+            "[package.metadata.include-file]\nstrict = \"yes\"\n",
+            "[package.metadata.include-file]\nmax-file-size = -1\n",
+            "[package.metadata.include-file]\nstict = true\n",
+        ] {
+            let err = content.parse::<Config>().expect_err(content);
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn check_strict() {
+        let config = Config {
+            strict: true,
+            ..Default::default()
+        };
+        let workspace = PathBuf::from("/workspace");
+        let allowed = [workspace.clone()];
+        config
+            .check(&workspace.join("README.md"), 1, &allowed)
+            .expect("expected file in workspace");
+
+        // A symlink or `..` resolved outside the workspace.
+        let err = config
+            .check(&PathBuf::from("/etc/passwd"), 1, &allowed)
+            .expect_err("expected file outside workspace");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = config
+            .check(&PathBuf::from("/workspace-other/README.md"), 1, &allowed)
+            .expect_err("expected file outside workspace");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        let err = config
+            .check(
+                &workspace.join("README.md"),
+                DEFAULT_MAX_FILE_SIZE + 1,
+                &allowed,
+            )
+            .expect_err("expected file too large");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn check_not_strict() {
+        let config = Config {
+            strict: false,
+            max_file_size: 0,
+            ..Default::default()
+        };
+        config
+            .check(
+                &PathBuf::from("/etc/passwd"),
+                1,
+                &[PathBuf::from("/workspace")],
+            )
+            .expect("expected any file");
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{
+    cache, manifest_dir, open, open_all, Config, Duplicates, MarkdownArgs, OpenFile, Root,
+};
+use include_file_core::{Block, Selector};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use syn::{
+    parse::{ParseStream, Parser},
+    parse2, LitStr,
+};
+
+const MACROS: &[&str] = &[
+    "include_asciidoc",
+    "include_markdown",
+    "include_org",
+    "include_textile",
+];
+
+pub fn assert_all_included(item: TokenStream) -> syn::Result<TokenStream> {
+    let path: LitStr = parse2(item)?;
+    let Coverage {
+        untested,
+        files,
+        root,
+    } = untested(&path.value()).map_err(|err| syn::Error::new(path.span(), err))?;
+
+    // Register the file and every source file searched as dependencies so changes to either check again.
+    let mut output = TokenStream::new();
+    if let Root::Env(var) = &root {
+        output.extend(quote! { const _: ::std::option::Option<&str> = ::std::option_env!(#var); });
+    }
+    for file in files {
+        let file = file.to_string_lossy();
+        output.extend(quote! { const _: &[u8] = ::std::include_bytes!(#file); });
+    }
+    if !untested.is_empty() {
+        let message = format!(
+            "Rust code blocks not included by any test:\n{}",
+            untested.join("\n")
+        );
+        output.extend(quote! {
+            ::std::panic!("{}", #message);
+        });
+    }
+    Ok(output)
+}
+
+/// The result of checking which Rust code blocks are included.
+struct Coverage {
+    /// The location and name of each Rust code block not included.
+    untested: Vec<String>,
+    /// The file checked followed by every source file searched.
+    files: Vec<PathBuf>,
+    /// The directory the file is relative to.
+    root: Root,
+}
+
+/// Finds each Rust code block in `path` not included by any source file in the crate.
+fn untested(path: &str) -> io::Result<Coverage> {
+    let manifest_dir = manifest_dir()?;
+    let config = cache::config(&manifest_dir)?;
+    let format = config.format(path)?;
+    let OpenFile {
+        file,
+        path: target,
+        display_path,
+        ..
+    } = open(&config, &config.root, path)?;
+    let blocks = format.collect(file, Some("rust"))?;
+
+    let mut included = vec![false; blocks.len()];
+    let mut sources = Vec::new();
+    find_sources(&manifest_dir, &mut sources)?;
+    for source in &sources {
+        let Ok(tokens) = fs::read_to_string(source)?.parse::<TokenStream>() else {
+            continue;
+        };
+        find_invocations(tokens, &config, &mut |args| {
+            let root = match args.relative {
+                Some(_) => Root::Source(source.clone()),
+                None => args.root,
+            };
+            let Ok(files) = open_all(&config, &root, &args.path.value()) else {
+                return;
+            };
+            if files.iter().any(|file| file.path == target) {
+                for i in selected(&args.selector, args.duplicates, &blocks) {
+                    included[i] = true;
+                }
+            }
+        });
+    }
+
+    let untested = blocks
+        .iter()
+        .zip(included)
+        .filter(|(block, included)| !included && !block.untested)
+        .map(|(block, _)| match block.name() {
+            Some(name) => format!("{display_path}:{} '{name}'", block.start_line),
+            None => format!("{display_path}:{}", block.start_line),
+        })
+        .collect();
+    let mut files = vec![target];
+    files.extend(sources);
+    Ok(Coverage {
+        untested,
+        files,
+        root: config.root.clone(),
+    })
+}
+
+/// Returns the indices of the `blocks` a macro includes with `selector` and `duplicates`.
+fn selected(selector: &Selector, duplicates: Option<Duplicates>, blocks: &[Block]) -> Vec<usize> {
+    let Selector::Name(name) = selector else {
+        return selector.find(blocks).into_iter().collect();
+    };
+    let mut named = blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| block.is_named(name))
+        .map(|(i, _)| i);
+    match duplicates {
+        Some(Duplicates::First) => named.take(1).collect(),
+        Some(Duplicates::Last) => named.next_back().into_iter().collect(),
+        // Without `duplicates`, a name found more than once fails to compile where it is included.
+        Some(Duplicates::All) | None => named.collect(),
+    }
+}
+
+/// Recursively finds Rust source files in `dir`, skipping build output, hidden directories, and other packages.
+fn find_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(fs::DirEntry::file_name);
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type()?.is_dir() {
+            if name == "target" || name.starts_with('.') || path.join("Cargo.toml").is_file() {
+                continue;
+            }
+            find_sources(&path, sources)?;
+        } else if name.ends_with(".rs") {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Calls `f` with the arguments of each `include_*!` macro invocation in `tokens`.
+fn find_invocations(tokens: TokenStream, config: &Config, f: &mut impl FnMut(MarkdownArgs)) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if MACROS.iter().any(|name| ident == name) => {
+                if let [TokenTree::Punct(bang), TokenTree::Group(group), ..] = &tokens[i + 1..] {
+                    if bang.as_char() == '!' {
+                        let parse = |input: ParseStream| MarkdownArgs::parse_with(input, config);
+                        if let Ok(args) = parse.parse2(group.stream()) {
+                            f(args);
+                        }
+                    }
+                }
+            }
+            TokenTree::Group(group) => find_invocations(group.stream(), config, f),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_invocations, selected};
+    use crate::{Config, Duplicates};
+    use include_file_core::{Block, Selector};
+    use quote::quote;
+
+    #[test]
+    fn selected_duplicates() {
+        let block = |name: &str| Block {
+            names: vec![name.into()],
+            ..Default::default()
+        };
+        let blocks = [block("example"), block("other"), block("example")];
+        let name = Selector::Name("example".into());
+        assert_eq!(selected(&name, None, &blocks), vec![0, 2]);
+        assert_eq!(selected(&name, Some(Duplicates::First), &blocks), vec![0]);
+        assert_eq!(selected(&name, Some(Duplicates::Last), &blocks), vec![2]);
+        assert_eq!(selected(&name, Some(Duplicates::All), &blocks), vec![0, 2]);
+        assert_eq!(selected(&Selector::Index(2), None, &blocks), vec![1]);
+        assert!(selected(&Selector::Index(4), None, &blocks).is_empty());
+    }
+
+    #[cfg(feature = "asciidoc")]
+    #[test]
+    fn assert_all_included_tracked() {
+        let actual = super::assert_all_included(quote! { "tests/README.adoc" })
+            .expect("expected tokens")
+            .to_string();
+        let tracked: Vec<_> = actual
+            .split("include_bytes !")
+            .skip(1)
+            .map(|s| s.split('"').nth(1).unwrap_or_default())
+            .collect();
+        assert!(tracked[0].ends_with("README.adoc"), "{actual}");
+        assert!(
+            tracked.iter().any(|path| path.ends_with("readme.rs")),
+            "{actual}"
+        );
+        assert!(!actual.contains("panic"), "{actual}");
+    }
+
+    #[test]
+    fn find_invocations_nested() {
+        let tokens = quote! {
+            #[test]
+            fn test() {
+                include_markdown!("README.md", "example");
+                {
+                    include_file::include_org!("README.org", index = 2, scope);
+                }
+            }
+            mod items {
+                include_asciidoc!("README.adoc", "items", items);
+            }
+        };
+        let mut actual = Vec::new();
+        find_invocations(tokens, &Config::default(), &mut |args| {
+            actual.push((args.path.value(), args.selector));
+        });
+        assert_eq!(
+            actual,
+            vec![
+                ("README.md".to_string(), Selector::Name("example".into())),
+                ("README.org".to_string(), Selector::Index(2)),
+                ("README.adoc".to_string(), Selector::Name("items".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_invocations_ignores_others() {
+        let tokens = quote! {
+            include_str!("README.md");
+            assert_all_included!("README.md");
+            include_markdown!("README.md");
+            include_markdown("README.md", "example");
+        };
+        let mut count = 0;
+        find_invocations(tokens, &Config::default(), &mut |_| count += 1);
+        assert_eq!(count, 0);
+    }
+}
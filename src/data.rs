@@ -0,0 +1,275 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{
+    cache, find_snippet, manifest, manifest_dir, name_value, pin, BlockArgs, Config, Duplicates,
+    Root, Snippet,
+};
+use include_file_core::{Block, Selector};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{fs, io};
+use syn::{
+    parse::{ParseStream, Parser},
+    spanned::Spanned,
+    LitStr,
+};
+
+/// The format of a data block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DataFormat {
+    Json,
+    Toml,
+}
+
+impl DataFormat {
+    /// Returns the 1-based line and column within `content` and a description of the first syntax error.
+    fn validate(self, content: &str) -> Result<(), (usize, usize, String)> {
+        match self {
+            Self::Json => serde_json::from_str::<serde_json::Value>(content)
+                .map(|_| ())
+                .map_err(|err| {
+                    // Remove the location serde_json appends since it is relative to the block.
+                    let message = err.to_string();
+                    let message = match message.rfind(" at line ") {
+                        Some(i) => message[..i].to_string(),
+                        None => message,
+                    };
+                    (err.line(), err.column(), message)
+                }),
+            Self::Toml => content.parse::<toml::Table>().map(|_| ()).map_err(|err| {
+                let offset = err.span().map_or(0, |span| span.start);
+                let line = content[..offset].matches('\n').count() + 1;
+                let column = offset - content[..offset].rfind('\n').map_or(0, |i| i + 1) + 1;
+                (line, column, err.message().to_string())
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => f.write_str("JSON"),
+            Self::Toml => f.write_str("TOML"),
+        }
+    }
+}
+
+struct DataArgs {
+    path: LitStr,
+    selector: Selector,
+    selector_span: Span,
+    format: DataFormat,
+    root: Root,
+    duplicates: Option<Duplicates>,
+    sha256: Option<LitStr>,
+}
+
+impl DataArgs {
+    /// Parses arguments, using defaults from `config` for any parameters not passed.
+    fn parse_with(input: ParseStream, config: &Config) -> syn::Result<Self> {
+        const REQ_PARAMS: &str = r#"missing required parameters ("path", "name", "format")"#;
+
+        let mut format = None;
+        let BlockArgs {
+            path,
+            selector,
+            selector_span,
+            root,
+            duplicates,
+            sha256,
+        } = BlockArgs::parse_with(input, REQ_PARAMS, |param| {
+            if !param.path().is_ident("format") {
+                return Err(syn::Error::new(param.span(), "unsupported parameter"));
+            }
+            let lit: LitStr = name_value(param)?;
+            format = Some(match lit.value().as_str() {
+                "json" => DataFormat::Json,
+                "toml" => DataFormat::Toml,
+                _ => return Err(syn::Error::new(lit.span(), r#"expected "json" or "toml""#)),
+            });
+            Ok(())
+        })?;
+        let format = format.ok_or_else(|| syn::Error::new(input.span(), REQ_PARAMS))?;
+        if duplicates == Some(Duplicates::All) {
+            // Joining code fences would not report errors at their lines, nor is it likely to be valid data.
+            return Err(syn::Error::new(
+                selector_span,
+                r#"duplicates = "all" is not supported for data; use "first" or "last""#,
+            ));
+        }
+        Ok(Self {
+            path,
+            selector,
+            selector_span,
+            format,
+            root: root.unwrap_or_else(|| config.root.clone()),
+            duplicates,
+            sha256,
+        })
+    }
+}
+
+pub fn include_markdown_data(item: TokenStream) -> syn::Result<TokenStream> {
+    include_data(item, |lines| {
+        include_file_core::markdown::collect_lang(lines, None)
+    })
+}
+
+fn include_data<F>(item: TokenStream, f: F) -> syn::Result<TokenStream>
+where
+    F: 'static + Copy + FnOnce(io::Lines<io::BufReader<fs::File>>) -> io::Result<Vec<Block>>,
+{
+    let config = manifest_dir()
+        .and_then(|dir| cache::config(&dir))
+        .map_err(|err| syn::Error::new(Span::call_site(), err))?;
+    let args = (|input: ParseStream| DataArgs::parse_with(input, &config)).parse2(item)?;
+    let Snippet {
+        display_path,
+        start_line,
+        content,
+        blocks,
+        indent,
+        tracked,
+        ..
+    } = find_snippet(
+        &config,
+        &args.root,
+        &args.path,
+        &args.selector,
+        args.selector_span,
+        args.duplicates,
+        f,
+    )?;
+    if let Some(sha256) = &args.sha256 {
+        pin::check(sha256, &args.selector, &content)?;
+    }
+
+    args.format
+        .validate(&content)
+        .map_err(|(line, column, err)| {
+            // Columns within the file include the indentation stripped from the code fence.
+            syn::Error::new(
+                args.selector_span,
+                format!(
+                    "invalid {} at {display_path}:{}:{}: {err}",
+                    args.format,
+                    start_line as usize + line - 1,
+                    column + indent,
+                ),
+            )
+        })?;
+    manifest::record(&display_path, &args.selector, &blocks)?;
+
+    Ok(quote! {
+        {
+            #tracked
+            #content
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{include_data, DataFormat};
+    use include_file_core::Block;
+    use quote::quote;
+    use std::io;
+
+    fn collect<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+        Ok(vec![
+            Block {
+                lang: Some("toml".into()),
+                names: vec!["config".into(), "settings".into()],
+                start_line: 3,
+                lines: vec!["[package]".into(), "name = \"example\"".into()],
+                ..Default::default()
+            },
+            Block {
+                lang: Some("json".into()),
+                names: vec!["payload".into()],
+                start_line: 8,
+                lines: vec!["{".into(), "  \"name\": example".into(), "}".into()],
+                indent: 2,
+                ..Default::default()
+            },
+            Block {
+                lang: Some("toml".into()),
+                names: vec!["settings".into()],
+                start_line: 13,
+                lines: vec!["[package]".into(), "name = example".into()],
+                ..Default::default()
+            },
+        ])
+    }
+
+    #[test]
+    fn include_data_toml() {
+        let tokens = quote! { "README.md", "config", format = "toml" };
+        let actual = include_data(tokens, collect).expect("expected include_data");
+        let expected = "[package]\nname = \"example\"";
+        assert!(
+            actual
+                .to_string()
+                .contains(&quote! { #expected }.to_string()),
+            "{actual}"
+        );
+    }
+
+    #[test]
+    fn include_data_json_err() {
+        let tokens = quote! { "README.md", "payload", format = "json" };
+        let err = include_data(tokens, collect).expect_err("expected invalid JSON");
+        assert_eq!(
+            err.to_string(),
+            "invalid JSON at README.md:9:13: expected value"
+        );
+    }
+
+    #[test]
+    fn include_data_duplicates() {
+        let tokens = quote! { "README.md", "settings", format = "toml", duplicates = "first" };
+        include_data(tokens, collect).expect("expected first settings");
+
+        let tokens = quote! { "README.md", "settings", format = "toml", duplicates = "last" };
+        let err = include_data(tokens, collect).expect_err("expected invalid TOML");
+        assert_eq!(
+            err.to_string(),
+            "invalid TOML at README.md:14:8: string values must be quoted, expected literal string"
+        );
+
+        let tokens = quote! { "README.md", "settings", format = "toml", duplicates = "all" };
+        let err = include_data(tokens, collect).expect_err("expected unsupported duplicates");
+        assert_eq!(
+            err.to_string(),
+            r#"duplicates = "all" is not supported for data; use "first" or "last""#
+        );
+    }
+
+    #[test]
+    fn include_data_params_err() {
+        for tokens in [
+            quote! { "README.md", "config" },
+            quote! { "README.md", "config", format = "yaml" },
+            quote! { "README.md", "config", format = "toml", scope },
+        ] {
+            include_data(tokens, collect).expect_err("expected invalid parameters");
+        }
+    }
+
+    #[test]
+    fn validate_toml_err() {
+        assert_eq!(
+            DataFormat::Toml.validate("[package]\nname = example\n"),
+            Err((
+                2,
+                8,
+                "string values must be quoted, expected literal string".into()
+            ))
+        );
+        assert!(DataFormat::Toml
+            .validate("[package]\nname = \"example\"\n")
+            .is_ok());
+    }
+}
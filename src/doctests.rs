@@ -0,0 +1,290 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{cache, manifest_dir, name_value, open, parse_param, Config, OpenFile, Root};
+use include_file_core::{markdown, Block};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::io::{self, BufRead};
+use syn::{
+    parse::{ParseStream, Parser},
+    spanned::Spanned,
+    LitStr, Token,
+};
+
+/// The name of a code fence containing setup code for other code fences.
+const PRELUDE: &str = "prelude";
+
+/// A line in a prelude separating the lines before a snippet from the lines after it.
+const SNIPPET: &str = "// snippet";
+
+struct DoctestsArgs {
+    path: LitStr,
+    prelude: Option<LitStr>,
+    root: Root,
+}
+
+impl DoctestsArgs {
+    /// Parses arguments, using defaults from `config` for any parameters not passed.
+    fn parse_with(input: ParseStream, config: &Config) -> syn::Result<Self> {
+        let path: LitStr = input.parse().map_err(|err| {
+            syn::Error::new(err.span(), r#"missing required string parameter ("path")"#)
+        })?;
+
+        let mut prelude = None;
+        let mut root = None;
+        if input.parse::<Token![,]>().is_ok() {
+            let params = input.parse_terminated(parse_param, Token![,])?;
+            for param in params {
+                if param.path().is_ident("prelude") {
+                    prelude = Some(name_value(&param)?);
+                } else if param.path().is_ident("root") {
+                    root = Some(Root::from_meta(&param)?);
+                } else {
+                    return Err(syn::Error::new(param.span(), "unsupported parameter"));
+                }
+            }
+        } else if !input.is_empty() {
+            return Err(syn::Error::new(input.span(), "unexpected token"));
+        }
+
+        Ok(Self {
+            path,
+            prelude,
+            root: root.unwrap_or_else(|| config.root.clone()),
+        })
+    }
+}
+
+/// Lines of setup code hidden before and after a snippet.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Prelude {
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+impl Prelude {
+    /// Splits `lines` into the lines before and after a `// snippet` line, or all before if there is none.
+    fn new(lines: &[String]) -> Self {
+        match lines.iter().position(|line| line.trim() == SNIPPET) {
+            Some(i) => Self {
+                before: lines[..i].to_vec(),
+                after: lines[i + 1..].to_vec(),
+            },
+            None => Self {
+                before: lines.to_vec(),
+                after: Vec::new(),
+            },
+        }
+    }
+
+    fn extend(&mut self, other: Prelude) {
+        self.before.extend(other.before);
+        // Setup code added later is nested within earlier setup code.
+        self.after.splice(..0, other.after);
+    }
+}
+
+pub fn include_markdown_doctests(item: TokenStream) -> syn::Result<TokenStream> {
+    let config = manifest_dir()
+        .and_then(|dir| cache::config(&dir))
+        .map_err(|err| syn::Error::new(Span::call_site(), err))?;
+    let args = (|input: ParseStream| DoctestsArgs::parse_with(input, &config)).parse2(item)?;
+
+    let prelude = match &args.prelude {
+        Some(path) => {
+            let (_, lines) = read_lines(&config, &args.root, &path.value())
+                .map_err(|err| syn::Error::new(path.span(), err))?;
+            Prelude::new(&lines)
+        }
+        None => Prelude::default(),
+    };
+    let (display_path, lines) = read_lines(&config, &args.root, &args.path.value())
+        .map_err(|err| syn::Error::new(args.path.span(), err))?;
+    let content = doctests(&lines, prelude, &display_path)
+        .map_err(|err| syn::Error::new(args.path.span(), err))?;
+
+    // The file cannot be tracked because attributes like `#![doc = ..]` require a literal.
+    Ok(quote! { #content })
+}
+
+/// Returns the path of the file to display in errors and its lines.
+fn read_lines(config: &Config, root: &Root, path: &str) -> io::Result<(String, Vec<String>)> {
+    let OpenFile {
+        file, display_path, ..
+    } = open(config, root, path)?;
+    let lines = io::BufReader::new(file)
+        .lines()
+        .collect::<io::Result<_>>()?;
+    Ok((display_path, lines))
+}
+
+/// Returns the Markdown `lines` with `prelude` and any `prelude` code fences hidden within each named Rust code fence.
+///
+/// Code fences named `prelude` are removed. Those with other names apply only to code fences with those names.
+fn doctests(lines: &[String], prelude: Prelude, display_path: &str) -> io::Result<String> {
+    let content = lines.join("\n");
+    let blocks = markdown::collect(io::BufReader::new(content.as_bytes()).lines())?;
+    // An unterminated code fence has no closing line to hide code after.
+    if let Some(block) = blocks.iter().find(|block| block.unterminated) {
+        return Err(block.unterminated_error(display_path));
+    }
+    let (preludes, blocks): (Vec<&Block>, Vec<&Block>) =
+        blocks.iter().partition(|block| block.is_named(PRELUDE));
+
+    // 0-based indices of the opening and closing fence lines.
+    let fences = |block: &Block| {
+        let open = block.open_line as usize - 1;
+        (open, open + block.lines.len() + 1)
+    };
+    let mut removed = vec![false; lines.len()];
+    for block in &preludes {
+        let (open, close) = fences(block);
+        removed[open..=close.min(lines.len() - 1)].fill(true);
+    }
+
+    let mut hidden = vec![(Vec::new(), Vec::new()); lines.len()];
+    for block in blocks.iter().filter(|block| block.name().is_some()) {
+        let mut all = Prelude {
+            before: prelude.before.clone(),
+            after: prelude.after.clone(),
+        };
+        for other in &preludes {
+            let targets: Vec<_> = other.names.iter().filter(|name| *name != PRELUDE).collect();
+            if targets.is_empty() || targets.iter().any(|name| block.is_named(name)) {
+                all.extend(Prelude::new(&other.lines));
+            }
+        }
+
+        // Indent hidden lines like the code fence e.g., within a list item.
+        let (open, close) = fences(block);
+        let indent = &lines[open][..lines[open].len() - lines[open].trim_start().len()];
+        let hide = |line: &String| match line.is_empty() {
+            true => format!("{indent}#"),
+            false => format!("{indent}# {line}"),
+        };
+        hidden[open].0 = all.before.iter().map(hide).collect();
+        if let Some(after) = hidden.get_mut(close) {
+            after.1 = all.after.iter().map(hide).collect();
+        }
+    }
+
+    let mut output = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        if removed[i] {
+            continue;
+        }
+        let (before, after) = &hidden[i];
+        output.extend(after.iter().cloned());
+        output.push(line.clone());
+        output.extend(before.iter().cloned());
+    }
+    let mut content = output.join("\n");
+    content.push('\n');
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{doctests, Prelude};
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn prelude_snippet() {
+        let prelude = Prelude::new(&lines(
+            "use std::error::Error;\nfn main() -> Result<(), Box<dyn Error>> {\n    // snippet\n    Ok(())\n}",
+        ));
+        assert_eq!(
+            prelude,
+            Prelude {
+                before: lines("use std::error::Error;\nfn main() -> Result<(), Box<dyn Error>> {"),
+                after: lines("    Ok(())\n}"),
+            }
+        );
+    }
+
+    #[test]
+    fn doctests_hidden() {
+        let content = lines(
+            r#"# Example
+
+<!--
+```rust prelude
+fn example() -> Result<i32, std::num::ParseIntError> { "1".parse() }
+```
+
+```rust prelude other
+let unused = 1;
+```
+-->
+
+```rust example
+let n = example()?;
+assert_eq!(n, 1);
+```
+
+- List:
+
+  ```rust other
+  let x = 1;
+  ```
+
+```rust
+let unnamed = 1;
+```
+"#,
+        );
+        let prelude = Prelude::new(&lines(
+            "fn main() -> Result<(), Box<dyn std::error::Error>> {\n// snippet\n\nOk(())\n}",
+        ));
+        assert_eq!(
+            doctests(&content, prelude, "README.md").expect("expected doctests"),
+            r#"# Example
+
+<!--
+
+-->
+
+```rust example
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# fn example() -> Result<i32, std::num::ParseIntError> { "1".parse() }
+let n = example()?;
+assert_eq!(n, 1);
+#
+# Ok(())
+# }
+```
+
+- List:
+
+  ```rust other
+  # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  # fn example() -> Result<i32, std::num::ParseIntError> { "1".parse() }
+  # let unused = 1;
+  let x = 1;
+  #
+  # Ok(())
+  # }
+  ```
+
+```rust
+let unnamed = 1;
+```
+"#
+        );
+    }
+
+    #[test]
+    fn doctests_unterminated_err() {
+        let content = lines("# Example\n\n```rust example\nlet x = 1;\n");
+        let err = doctests(&content, Prelude::default(), "README.md")
+            .expect_err("expected unterminated code fence");
+        assert_eq!(
+            err.to_string(),
+            "unterminated code fence 'example' opened at README.md:3"
+        );
+    }
+}
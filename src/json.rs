@@ -0,0 +1,24 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+// Included in both the manifest and the guard expanded by macros, since expanded code cannot call this crate.
+// Paths are fully qualified to avoid any conflicting names where it's expanded.
+
+/// Returns `s` as a JSON string, escaping quotes, backslashes, and control characters.
+fn json_string(s: &str) -> ::std::string::String {
+    let mut json = ::std::string::String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => json.push_str(&::std::format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -4,33 +4,73 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
-#[cfg(feature = "asciidoc")]
-mod asciidoc;
-mod markdown;
-#[cfg(feature = "org")]
-mod org;
+mod cache;
+mod config;
+mod coverage;
+mod data;
+mod doctests;
+mod manifest;
+mod pin;
+mod replace;
+mod section;
+mod statements;
+mod substitute;
 #[cfg(test)]
 mod tests;
-#[cfg(feature = "textile")]
-mod textile;
 
+use config::Config;
+use include_file_core::{parse_range, select, Block, Duplicates, Selector};
 use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
-use quote::quote;
+use quote::{quote, ToTokens};
+use replace::Replacement;
 use std::{
-    env, fs,
-    io::{self, BufRead},
-    path::PathBuf,
+    env, fs, io,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicU64, Ordering},
 };
 use syn::{
-    parse::{Parse, ParseStream},
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
     parse2,
     spanned::Spanned,
-    LitStr, Meta, Token,
+    token, Expr, ExprLit, ExprMacro, Lit, LitBool, LitInt, LitStr, Meta, MetaNameValue, Token,
 };
 
 static INCLUDE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Assert that every Rust code block in a file is included by a test in this crate.
+///
+/// All `.rs` files in the crate are searched for `include_asciidoc!`, `include_markdown!`, `include_org!`, and `include_textile!`
+/// macros referencing the file, excluding the `target` directory, hidden directories, and other packages.
+/// The format of the file is determined by its extension.
+///
+/// Panics listing the location and name of each Rust code block not included.
+/// A code block can opt out by adding an `untested` attribute to its info string e.g., `rust untested`;
+/// to its attribute list in AsciiDoc e.g., `[,rust,untested]`; as an `:untested` header argument in Org;
+/// or as an `untested` class in Textile e.g., `bc(rust untested)`.
+///
+/// The file and every source file searched are tracked, so changing any of them checks again.
+/// New source files are not tracked, however, so you may need to `touch` a source file to check again.
+///
+/// # Arguments
+///
+/// * `path` (*Required*) Path relative to the crate root directory, or the default `root` from the crate configuration.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[test]
+/// fn readme_included() {
+///     include_file::assert_all_included!("README.md");
+/// }
+/// ```
+#[proc_macro]
+pub fn assert_all_included(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    coverage::assert_all_included(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// Include code from within a source block in an AsciiDoc file.
 ///
 /// All AsciiDoc [source blocks](https://docs.asciidoctor.org/asciidoc/latest/verbatim/source-blocks/)
@@ -38,9 +78,22 @@ static INCLUDE_COUNTER: AtomicU64 = AtomicU64::new(0);
 ///
 /// # Arguments
 ///
-/// * `path` (*Required*) Path relative to the crate root directory.
-/// * `name` (*Required*) Name of the code fence to include.
+/// * `path` (*Required*) Path relative to the crate root directory, or a glob pattern like `"docs/**/*.adoc"` to search each matching file for exactly one code fence with the `name`.
+/// * `name` Name of the code fence to include. Required unless `index` or `lines` is passed.
+/// * `index` 1-based ordinal of the Rust code fence to include e.g., `index = 3`.
+/// * `lines` Range of lines within a single Rust code fence to include e.g., `lines = "40..55"`.
 /// * `scope` Include the snippet in braces `{ .. }`.
+/// * `statements` Include the snippet as statements, overriding a default `mode` from the crate configuration.
+/// * `items` Include the snippet as items like `struct` or `fn` definitions e.g., at module scope.
+/// * `expr` Include the snippet as an expression that evaluates to the snippet's tail expression.
+/// * `doctest` Include the snippet like rustdoc compiles a doctest e.g., calling `fn main`, returning a `Result` for `?`, or declaring `extern crate` for the package library it names.
+/// * `async` Include the snippet in an `async` block driven by a `runtime`.
+/// * `runtime` The runtime used by `async`: `"tokio"` (default), `"async_std"`, or a path to a `block_on` function.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+/// * `replace` Replace text before including the snippet e.g., `replace = [("https://api.example.com", "http://127.0.0.1:8080")]`, or a regular expression e.g., `replace = [(regex(r"v\d+"), "v2")]`.
+/// * `substitute` Replace `{{version}}`, `{{crate_name}}`, and `{{env:VAR}}` placeholders before including the snippet.
+/// * `duplicates` Include the `"first"`, `"last"`, or `"all"` code fences with the `name` instead of failing when a file has more than one.
+/// * `sha256` Fail if the SHA-256 hash of the code fence no longer matches e.g., `sha256 = "aad7378d.."`. Run `cargo include-file pin <path> --write <source>` to rewrite changed hashes.
 /// * `relative` (*Requires rustc 1.88 or newer*) Path is relative to the source file calling the macro.
 ///
 /// # Examples
@@ -75,9 +128,12 @@ static INCLUDE_COUNTER: AtomicU64 = AtomicU64::new(0);
 #[cfg(feature = "asciidoc")]
 #[proc_macro]
 pub fn include_asciidoc(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    asciidoc::include_asciidoc(item.into())
-        .unwrap_or_else(syn::Error::into_compile_error)
-        .into()
+    include_file(
+        item.into(),
+        include_file_core::asciidoc::collect::<fs::File>,
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
 }
 
 /// Include code from within a code fence in a Markdown file.
@@ -86,9 +142,22 @@ pub fn include_asciidoc(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
 ///
 /// # Arguments
 ///
-/// * `path` (*Required*) Path relative to the crate root directory.
-/// * `name` (*Required*) Name of the code fence to include.
+/// * `path` (*Required*) Path relative to the crate root directory, or a glob pattern like `"docs/**/*.md"` to search each matching file for exactly one code fence with the `name`.
+/// * `name` Name of the code fence to include. Required unless `index` or `lines` is passed.
+/// * `index` 1-based ordinal of the Rust code fence to include e.g., `index = 3`.
+/// * `lines` Range of lines within a single Rust code fence to include e.g., `lines = "40..55"`.
 /// * `scope` Include the snippet in braces `{ .. }`.
+/// * `statements` Include the snippet as statements, overriding a default `mode` from the crate configuration.
+/// * `items` Include the snippet as items like `struct` or `fn` definitions e.g., at module scope.
+/// * `expr` Include the snippet as an expression that evaluates to the snippet's tail expression.
+/// * `doctest` Include the snippet like rustdoc compiles a doctest e.g., calling `fn main`, returning a `Result` for `?`, or declaring `extern crate` for the package library it names.
+/// * `async` Include the snippet in an `async` block driven by a `runtime`.
+/// * `runtime` The runtime used by `async`: `"tokio"` (default), `"async_std"`, or a path to a `block_on` function.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+/// * `replace` Replace text before including the snippet e.g., `replace = [("https://api.example.com", "http://127.0.0.1:8080")]`, or a regular expression e.g., `replace = [(regex(r"v\d+"), "v2")]`.
+/// * `substitute` Replace `{{version}}`, `{{crate_name}}`, and `{{env:VAR}}` placeholders before including the snippet.
+/// * `duplicates` Include the `"first"`, `"last"`, or `"all"` code fences with the `name` instead of failing when a file has more than one.
+/// * `sha256` Fail if the SHA-256 hash of the code fence no longer matches e.g., `sha256 = "aad7378d.."`. Run `cargo include-file pin <path> --write <source>` to rewrite changed hashes.
 /// * `relative` (*Requires rustc 1.88 or newer*) Path is relative to the source file calling the macro.
 ///
 /// # Examples
@@ -123,7 +192,158 @@ pub fn include_asciidoc(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
 /// ```
 #[proc_macro]
 pub fn include_markdown(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    markdown::include_markdown(item.into())
+    include_file(
+        item.into(),
+        include_file_core::markdown::collect::<fs::File>,
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Include the content of a data code fence in a Markdown file as a `&'static str`, validated at compile time.
+///
+/// Code fences in any language may be selected. The content must parse as the given `format`,
+/// or compilation fails reporting the line of the syntax error within the Markdown file.
+///
+/// # Arguments
+///
+/// * `path` (*Required*) Path relative to the crate root directory, or a glob pattern like `"docs/**/*.md"` to search each matching file for exactly one code fence with the `name`.
+/// * `name` Name of the code fence to include. Required unless `index` or `lines` is passed.
+/// * `format` (*Required*) Format of the content: `"json"` or `"toml"`.
+/// * `index` 1-based ordinal of the code fence in any language to include e.g., `index = 3`.
+/// * `lines` Range of lines within a single code fence to include e.g., `lines = "40..55"`.
+/// * `duplicates` Include the `"first"`, `"last"`, or `"all"` code fences with the `name` instead of failing when a file has more than one.
+/// * `sha256` Fail if the SHA-256 hash of the code fence no longer matches e.g., `sha256 = "aad7378d.."`. Run `cargo include-file pin <path> --write <source>` to rewrite changed hashes.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+///
+/// # Examples
+///
+/// Consider the following code fence in a crate `README.md` Markdown file:
+///
+/// ````markdown
+/// ```toml config
+/// [package.metadata.include-file]
+/// root = "workspace"
+/// ```
+/// ````
+///
+/// We can make sure the configuration is valid and deserialize it in our Rust tests:
+///
+/// ```no_run
+/// #[test]
+/// fn test_config() {
+///     let config: toml::Table = include_markdown_data!("README.md", "config", format = "toml")
+///         .parse()
+///         .unwrap();
+///     assert_eq!(config["package"]["metadata"]["include-file"]["root"].as_str(), Some("workspace"));
+/// }
+/// ```
+#[proc_macro]
+pub fn include_markdown_data(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    data::include_markdown_data(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Include an AsciiDoc, Org, or Textile file, or a section under a heading, converted to Markdown as a string literal.
+///
+/// Rustdoc only renders Markdown, so this converts headings, lists, links, emphasis, tables, and source blocks
+/// to [CommonMark](https://commonmark.org) at compile time. Markdown files are included as-is.
+/// The expanded literal can be used in attributes like `#![doc = ..]`, which also means changes to the file are not tracked:
+/// you may need to `touch` a source file to rebuild.
+///
+/// # Arguments
+///
+/// * `path` (*Required*) Path relative to the crate root directory.
+/// * `heading` Text of the heading to include only its section e.g., `"Usage"`.
+/// * `demote` Number of levels to demote every heading e.g., `demote = 1` to include `== Usage` as `### Usage`.
+/// * `drop_badges` Remove badges like `[![CI](https://.../badge.svg)](https://...)`.
+/// * `base_url` Rewrite relative links to absolute URLs under the workspace root e.g., `base_url = "https://github.com/heaths/include-file/blob/main"`.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+///
+/// # Examples
+///
+/// Use a crate `README.adoc` AsciiDoc file as the crate documentation:
+///
+/// ```ignore
+/// #![doc = include_file::include_doc_as_markdown!("README.adoc")]
+/// ```
+#[proc_macro]
+pub fn include_doc_as_markdown(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    section::include_doc_as_markdown(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Include a Markdown file as a string literal with hidden setup code in each named Rust code fence, so they run as doctests.
+///
+/// Setup code from a `prelude` file and any Rust code fences named `prelude` is added to each named Rust code fence
+/// as hidden `# ` lines. Lines after a `// snippet` line in the setup code are added after the snippet e.g., to close a `fn main`.
+/// Code fences named `prelude` are removed, and apply only to code fences with any other names they have e.g., ```` ```rust prelude example ````.
+/// Wrap them in an HTML comment `<!-- -->` to hide them when rendered elsewhere e.g., on GitHub.
+///
+/// The expanded literal can be used in attributes like `#![doc = ..]`, which also means changes to the file are not tracked:
+/// you may need to `touch` a source file to rebuild.
+///
+/// # Arguments
+///
+/// * `path` (*Required*) Path relative to the crate root directory.
+/// * `prelude` Path to a Rust source file with setup code for every named Rust code fence e.g., a sibling `README.rs`.
+/// * `root` Directory the `path` and `prelude` are relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+///
+/// # Examples
+///
+/// Consider a `README.rs` file next to a crate's `README.md`:
+///
+/// ```no_run
+/// # #[derive(Debug)] struct Model { name: String }
+/// fn example() -> Result<Model, Box<dyn std::error::Error>> {
+///     Ok(Model { name: "example".into() })
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // snippet
+///     Ok(())
+/// }
+/// ```
+///
+/// Use the `README.md` as the crate documentation and run each named Rust code fence as a doctest with `cargo test --doc`:
+///
+/// ```ignore
+/// #![doc = include_file::include_markdown_doctests!("README.md", prelude = "README.rs")]
+/// ```
+#[proc_macro]
+pub fn include_markdown_doctests(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    doctests::include_markdown_doctests(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Include a section of prose under a heading in a Markdown, AsciiDoc, Org, or Textile file as a Markdown string literal.
+///
+/// The section includes the heading and any subsections up to the next heading of the same or a higher level.
+/// AsciiDoc, Org, and Textile files are converted to Markdown like [`include_doc_as_markdown!`]. The expanded literal can be used in attributes
+/// like `#![doc = ..]`, which also means changes to the file are not tracked: you may need to `touch` a source file to rebuild.
+///
+/// # Arguments
+///
+/// * `path` (*Required*) Path relative to the crate root directory.
+/// * `heading` (*Required*) Text of the heading e.g., `"Usage"`.
+/// * `demote` Number of levels to demote every heading e.g., `demote = 1` to include `## Usage` as `### Usage`.
+/// * `drop_badges` Remove badges like `[![CI](https://.../badge.svg)](https://...)`.
+/// * `base_url` Rewrite relative links to absolute URLs under the workspace root e.g., `base_url = "https://github.com/heaths/include-file/blob/main"`.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+///
+/// # Examples
+///
+/// Include the "Usage" section of the `README.md` in the crate documentation:
+///
+/// ```ignore
+/// #![doc = include_file::include_markdown_section!("README.md", "Usage", demote = 1, drop_badges)]
+/// ```
+#[proc_macro]
+pub fn include_markdown_section(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    section::include_markdown_section(item.into())
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
@@ -134,9 +354,22 @@ pub fn include_markdown(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
 ///
 /// # Arguments
 ///
-/// * `path` (*Required*) Path relative to the crate root directory.
-/// * `name` (*Required*) Name of the code fence to include.
+/// * `path` (*Required*) Path relative to the crate root directory, or a glob pattern like `"docs/**/*.textile"` to search each matching file for exactly one code fence with the `name`.
+/// * `name` Name of the code fence to include. Required unless `index` or `lines` is passed.
+/// * `index` 1-based ordinal of the Rust code fence to include e.g., `index = 3`.
+/// * `lines` Range of lines within a single Rust code fence to include e.g., `lines = "40..55"`.
 /// * `scope` Include the snippet in braces `{ .. }`.
+/// * `statements` Include the snippet as statements, overriding a default `mode` from the crate configuration.
+/// * `items` Include the snippet as items like `struct` or `fn` definitions e.g., at module scope.
+/// * `expr` Include the snippet as an expression that evaluates to the snippet's tail expression.
+/// * `doctest` Include the snippet like rustdoc compiles a doctest e.g., calling `fn main`, returning a `Result` for `?`, or declaring `extern crate` for the package library it names.
+/// * `async` Include the snippet in an `async` block driven by a `runtime`.
+/// * `runtime` The runtime used by `async`: `"tokio"` (default), `"async_std"`, or a path to a `block_on` function.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+/// * `replace` Replace text before including the snippet e.g., `replace = [("https://api.example.com", "http://127.0.0.1:8080")]`, or a regular expression e.g., `replace = [(regex(r"v\d+"), "v2")]`.
+/// * `substitute` Replace `{{version}}`, `{{crate_name}}`, and `{{env:VAR}}` placeholders before including the snippet.
+/// * `duplicates` Include the `"first"`, `"last"`, or `"all"` code fences with the `name` instead of failing when a file has more than one.
+/// * `sha256` Fail if the SHA-256 hash of the code fence no longer matches e.g., `sha256 = "aad7378d.."`. Run `cargo include-file pin <path> --write <source>` to rewrite changed hashes.
 /// * `relative` (*Requires rustc 1.88 or newer*) Path is relative to the source file calling the macro.
 ///
 /// # Examples
@@ -170,7 +403,7 @@ pub fn include_markdown(item: proc_macro::TokenStream) -> proc_macro::TokenStrea
 #[cfg(feature = "textile")]
 #[proc_macro]
 pub fn include_textile(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    textile::include_textile(item.into())
+    include_file(item.into(), include_file_core::textile::collect::<fs::File>)
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
@@ -181,9 +414,22 @@ pub fn include_textile(item: proc_macro::TokenStream) -> proc_macro::TokenStream
 ///
 /// # Arguments
 ///
-/// * `path` (*Required*) Path relative to the crate root directory.
-/// * `name` (*Required*) Name of the code fence to include.
+/// * `path` (*Required*) Path relative to the crate root directory, or a glob pattern like `"docs/**/*.org"` to search each matching file for exactly one code fence with the `name`.
+/// * `name` Name of the code fence to include. Required unless `index` or `lines` is passed.
+/// * `index` 1-based ordinal of the Rust code fence to include e.g., `index = 3`.
+/// * `lines` Range of lines within a single Rust code fence to include e.g., `lines = "40..55"`.
 /// * `scope` Include the snippet in braces `{ .. }`.
+/// * `statements` Include the snippet as statements, overriding a default `mode` from the crate configuration.
+/// * `items` Include the snippet as items like `struct` or `fn` definitions e.g., at module scope.
+/// * `expr` Include the snippet as an expression that evaluates to the snippet's tail expression.
+/// * `doctest` Include the snippet like rustdoc compiles a doctest e.g., calling `fn main`, returning a `Result` for `?`, or declaring `extern crate` for the package library it names.
+/// * `async` Include the snippet in an `async` block driven by a `runtime`.
+/// * `runtime` The runtime used by `async`: `"tokio"` (default), `"async_std"`, or a path to a `block_on` function.
+/// * `root` Directory the `path` is relative to: `"package"` (default), `"workspace"`, `"out_dir"`, or an environment variable like `env!("DOCS_DIR")`.
+/// * `replace` Replace text before including the snippet e.g., `replace = [("https://api.example.com", "http://127.0.0.1:8080")]`, or a regular expression e.g., `replace = [(regex(r"v\d+"), "v2")]`.
+/// * `substitute` Replace `{{version}}`, `{{crate_name}}`, and `{{env:VAR}}` placeholders before including the snippet.
+/// * `duplicates` Include the `"first"`, `"last"`, or `"all"` code fences with the `name` instead of failing when a file has more than one.
+/// * `sha256` Fail if the SHA-256 hash of the code fence no longer matches e.g., `sha256 = "aad7378d.."`. Run `cargo include-file pin <path> --write <source>` to rewrite changed hashes.
 /// * `relative` (*Requires rustc 1.88 or newer*) Path is relative to the source file calling the macro.
 ///
 /// # Examples
@@ -220,84 +466,725 @@ pub fn include_textile(item: proc_macro::TokenStream) -> proc_macro::TokenStream
 #[cfg(feature = "org")]
 #[proc_macro]
 pub fn include_org(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    org::include_org(item.into())
+    include_file(item.into(), include_file_core::org::collect::<fs::File>)
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
 
+/// Parameters shared by macros that include a code block.
+struct BlockArgs {
+    path: LitStr,
+    selector: Selector,
+    selector_span: Span,
+    root: Option<Root>,
+    duplicates: Option<Duplicates>,
+    sha256: Option<LitStr>,
+}
+
+impl BlockArgs {
+    /// Parses the `path`, an optional `name`, and any shared parameters, passing other parameters to `f`.
+    ///
+    /// The `required` message is the error if the `path` or a selector is missing.
+    fn parse_with(
+        input: ParseStream,
+        required: &str,
+        mut f: impl FnMut(&Meta) -> syn::Result<()>,
+    ) -> syn::Result<Self> {
+        let path: LitStr = input
+            .parse()
+            .map_err(|err| syn::Error::new(err.span(), required))?;
+        input.parse::<Token![,]>()?;
+
+        // The name may be omitted if another selector like `index` or `lines` is passed.
+        let mut selector = None;
+        if !input.peek(Ident::peek_any) {
+            let name: LitStr = input
+                .parse()
+                .map_err(|err| syn::Error::new(err.span(), required))?;
+            selector = Some((Selector::Name(name.value()), name.span()));
+        }
+
+        let mut root = None;
+        let mut duplicates = None;
+        let mut sha256 = None;
+        if selector.is_none() || input.parse::<Token![,]>().is_ok() {
+            let params = input.parse_terminated(parse_param, Token![,])?;
+            for param in params {
+                if param.path().is_ident("root") {
+                    root = Some(Root::from_meta(&param)?);
+                } else if param.path().is_ident("duplicates") {
+                    duplicates = Some(parse_duplicates(&param)?);
+                } else if param.path().is_ident("sha256") {
+                    sha256 = Some(pin::from_meta(&param)?);
+                } else if param.path().is_ident("index") {
+                    let lit: LitInt = name_value(&param)?;
+                    let index = lit.base10_parse()?;
+                    if index == 0 {
+                        return Err(syn::Error::new(lit.span(), "index is 1-based"));
+                    }
+                    set_selector(&mut selector, Selector::Index(index), param.span())?;
+                } else if param.path().is_ident("lines") {
+                    let lit: LitStr = name_value(&param)?;
+                    let range = parse_range(&lit.value())
+                        .map_err(|err| syn::Error::new(lit.span(), err))?;
+                    set_selector(&mut selector, Selector::Lines(range), param.span())?;
+                } else {
+                    f(&param)?;
+                }
+            }
+        } else if !input.is_empty() {
+            return Err(syn::Error::new(input.span(), "unexpected token"));
+        }
+
+        let (selector, selector_span) =
+            selector.ok_or_else(|| syn::Error::new(input.span(), required))?;
+        if is_glob(&path.value()) && !matches!(selector, Selector::Name(_)) {
+            return Err(syn::Error::new(
+                selector_span,
+                r#"a glob path requires a "name""#,
+            ));
+        }
+
+        Ok(Self {
+            path,
+            selector,
+            selector_span,
+            root,
+            duplicates,
+            sha256,
+        })
+    }
+}
+
 struct MarkdownArgs {
     path: LitStr,
-    name: LitStr,
+    selector: Selector,
+    selector_span: Span,
+    mode: Mode,
+    runtime: Option<Runtime>,
+    root: Root,
     scope: Option<Span>,
     relative: Option<Span>,
+    substitute: Option<Span>,
+    replace: Vec<Replacement>,
+    duplicates: Option<Duplicates>,
+    sha256: Option<LitStr>,
 }
 
 impl Parse for MarkdownArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        const REQ_PARAMS: &str = r#"missing required string parameters ("path", "name")"#;
+        Self::parse_with(input, &Config::default())
+    }
+}
 
-        let path = input
-            .parse()
-            .map_err(|err| syn::Error::new(err.span(), REQ_PARAMS))?;
-        input.parse::<Token![,]>()?;
-        let name = input
-            .parse()
-            .map_err(|err| syn::Error::new(err.span(), REQ_PARAMS))?;
+impl MarkdownArgs {
+    /// Parses arguments, using defaults from `config` for any parameters not passed.
+    fn parse_with(input: ParseStream, config: &Config) -> syn::Result<Self> {
+        const REQ_PARAMS: &str = r#"missing required string parameters ("path", "name")"#;
 
+        let mut mode = None;
+        let mut is_async = false;
+        let mut runtime = None;
         let mut scope = None;
         let mut relative = None;
+        let mut substitute = None;
+        let mut replace = Vec::new();
 
-        if input.parse::<Token![,]>().is_ok() {
-            let params = input.parse_terminated(Meta::parse, Token![,])?;
-            for param in params {
-                if param.path().is_ident("scope") {
-                    scope = Some(param.span());
-                } else if param.path().is_ident("relative") {
-                    relative = Some(param.span());
-                } else {
-                    return Err(syn::Error::new(param.span(), "unsupported parameter"));
-                }
+        let BlockArgs {
+            path,
+            selector,
+            selector_span,
+            root,
+            duplicates,
+            sha256,
+        } = BlockArgs::parse_with(input, REQ_PARAMS, |param| {
+            if param.path().is_ident("scope") {
+                // Passing `scope = false` overrides a default `scope` from the crate configuration.
+                let enabled = match param {
+                    Meta::Path(_) => true,
+                    _ => name_value::<LitBool>(param)?.value,
+                };
+                scope = Some(enabled.then_some(param.span()));
+            } else if param.path().is_ident("relative") {
+                relative = Some(param.span());
+            } else if param.path().is_ident("substitute") {
+                substitute = Some(param.span());
+            } else if param.path().is_ident("replace") {
+                replace = Replacement::from_meta(param)?;
+            } else if param.path().is_ident("statements") {
+                set_mode(&mut mode, Mode::Statements, param.span())?;
+            } else if param.path().is_ident("items") {
+                set_mode(&mut mode, Mode::Items, param.span())?;
+            } else if param.path().is_ident("expr") {
+                set_mode(&mut mode, Mode::Expr, param.span())?;
+            } else if param.path().is_ident("doctest") {
+                set_mode(&mut mode, Mode::Doctest, param.span())?;
+            } else if param.path().is_ident("async") {
+                is_async = true;
+            } else if param.path().is_ident("runtime") {
+                let lit: LitStr = name_value(param)?;
+                runtime = Some(lit.parse()?);
+            } else {
+                return Err(syn::Error::new(param.span(), "unsupported parameter"));
             }
-        } else if !input.is_empty() {
-            return Err(syn::Error::new(input.span(), "unexpected token"));
+            Ok(())
+        })?;
+
+        let (mode, mode_span) = mode.unwrap_or((config.mode, Span::call_site()));
+        let scope = match scope {
+            Some(scope) => scope,
+            None if config.scope && mode != Mode::Items => Some(Span::call_site()),
+            None => None,
+        };
+        if let (Some(span), Some(_)) = (relative, &root) {
+            return Err(syn::Error::new(
+                span,
+                r#""relative" cannot be used with "root""#,
+            ));
+        }
+        let root = match root {
+            Some(root) => root,
+            None if relative.is_some() => Root::Manifest,
+            None => config.root.clone(),
+        };
+        if is_async {
+            runtime.get_or_insert(Runtime::Tokio);
+        }
+        if mode == Mode::Items && scope.is_some() {
+            return Err(syn::Error::new(
+                mode_span,
+                r#""items" cannot be included in a "scope""#,
+            ));
+        }
+        if matches!(mode, Mode::Items | Mode::Doctest) && runtime.is_some() {
+            return Err(syn::Error::new(
+                mode_span,
+                r#""items" or "doctest" cannot be included in an "async" block"#,
+            ));
         }
 
         Ok(Self {
             path,
-            name,
+            selector,
+            selector_span,
+            mode,
+            runtime,
+            root,
             scope,
             relative,
+            substitute,
+            replace,
+            duplicates,
+            sha256,
+        })
+    }
+}
+
+/// The directory a `path` is relative to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum Root {
+    /// The crate root directory containing `Cargo.toml`.
+    #[default]
+    Manifest,
+    /// The directory containing a source file, relative to the crate root directory.
+    Source(PathBuf),
+    /// The workspace root directory containing the `[workspace]` `Cargo.toml`.
+    Workspace,
+    /// The `OUT_DIR` directory of the crate's build script.
+    OutDir,
+    /// The directory in an environment variable, relative to the crate root directory.
+    Env(String),
+}
+
+impl Root {
+    /// Returns the root named `"package"`, `"workspace"`, or `"out_dir"`.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "package" => Some(Self::Manifest),
+            "workspace" => Some(Self::Workspace),
+            "out_dir" => Some(Self::OutDir),
+            _ => None,
+        }
+    }
+
+    /// Parses `root = "package"`, `root = "workspace"`, `root = "out_dir"`, or `root = env!("VAR")`.
+    fn from_meta(meta: &Meta) -> syn::Result<Self> {
+        const EXPECTED: &str = r#"expected "package", "workspace", "out_dir", or env!("VAR")"#;
+        let Meta::NameValue(MetaNameValue { value, .. }) = meta else {
+            return Err(syn::Error::new(meta.span(), EXPECTED));
+        };
+        match value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => {
+                Self::from_name(&lit.value()).ok_or_else(|| syn::Error::new(lit.span(), EXPECTED))
+            }
+            Expr::Macro(ExprMacro { mac, .. }) if mac.path.is_ident("env") => {
+                let var: LitStr = mac.parse_body()?;
+                Ok(Self::Env(var.value()))
+            }
+            _ => Err(syn::Error::new(value.span(), EXPECTED)),
+        }
+    }
+
+    /// Returns the directory paths are relative to.
+    fn dir(&self, manifest_dir: &Path) -> io::Result<PathBuf> {
+        match self {
+            Self::Manifest => Ok(manifest_dir.to_path_buf()),
+            Self::Source(src) => src
+                .parent()
+                .map(|dir| manifest_dir.join(dir))
+                .ok_or_else(|| io::Error::other("no source parent directory")),
+            Self::Workspace => workspace_dir(manifest_dir),
+            Self::OutDir => env::var_os("OUT_DIR").map(PathBuf::from).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "OUT_DIR is not set; does the crate have a build script?",
+                )
+            }),
+            Self::Env(var) => env::var_os(var)
+                .map(|dir| manifest_dir.join(dir))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("environment variable {var} is not set"),
+                    )
+                }),
+        }
+    }
+}
+
+/// Returns the workspace root directory containing `manifest_dir`, or `manifest_dir` if it is not a workspace member.
+fn workspace_dir(manifest_dir: &Path) -> io::Result<PathBuf> {
+    for dir in manifest_dir.ancestors() {
+        let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        let manifest: toml::Table = content
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let Some(workspace) = manifest.get("workspace").and_then(toml::Value::as_table) else {
+            continue;
+        };
+
+        // A package excluded from the workspace is its own workspace root.
+        let excluded = workspace
+            .get("exclude")
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_str)
+            .any(|exclude| manifest_dir.starts_with(dir.join(exclude)));
+        return Ok(if excluded {
+            manifest_dir.to_path_buf()
+        } else {
+            dir.to_path_buf()
+        });
+    }
+    Ok(manifest_dir.to_path_buf())
+}
+
+/// How the included snippet is emitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Mode {
+    /// Statements within a function body, guarded to report the file, lines, and statement on panic.
+    #[default]
+    Statements,
+    /// Items like `struct`, `impl`, or `fn` definitions emitted as-is.
+    Items,
+    /// A block expression that evaluates to the snippet's tail expression.
+    Expr,
+    /// Statements wrapped in a function like rustdoc does for doctests, guarded like `Statements`.
+    Doctest,
+}
+
+/// The runtime used to drive an `async` snippet to completion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Runtime {
+    Tokio,
+    AsyncStd,
+    /// A path to a function like `fn block_on<F: Future>(future: F) -> F::Output`.
+    Custom(String),
+}
+
+impl Parse for Runtime {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: syn::Path = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error("expected a path to a `block_on` function"));
+        }
+        Ok(if path.is_ident("tokio") {
+            Self::Tokio
+        } else if path.is_ident("async_std") {
+            Self::AsyncStd
+        } else {
+            Self::Custom(path.to_token_stream().to_string())
         })
     }
 }
 
+impl Runtime {
+    /// Returns an expression that blocks on the `future` expression until complete.
+    fn block_on(&self, future: TokenStream) -> syn::Result<TokenStream> {
+        Ok(match self {
+            Self::Tokio => quote! {
+                ::tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build tokio runtime")
+                    .block_on(#future)
+            },
+            Self::AsyncStd => quote! { ::async_std::task::block_on(#future) },
+            Self::Custom(path) => {
+                let path: syn::Path = syn::parse_str(path)?;
+                quote! { #path(#future) }
+            }
+        })
+    }
+}
+
+/// The environment variable selecting the format of notes printed when included code panics: `github` or `json`.
+const NOTE_FORMAT_VAR: &str = "INCLUDE_FILE_NOTE_FORMAT";
+
+/// Parses `duplicates = "first"`, `duplicates = "last"`, or `duplicates = "all"`.
+fn parse_duplicates(meta: &Meta) -> syn::Result<Duplicates> {
+    let lit: LitStr = name_value(meta)?;
+    lit.value()
+        .parse()
+        .map_err(|err: String| syn::Error::new(lit.span(), err))
+}
+
+/// Returns whether the `tokens` use the `?` operator outside of any nested `async` block.
+fn uses_try(tokens: TokenStream) -> bool {
+    let mut after_async = false;
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == '?' => return true,
+            TokenTree::Group(g) if !after_async && uses_try(g.stream()) => return true,
+            _ => {}
+        }
+        // An `async` block has its own return type, so skip it and any `move` keyword.
+        after_async =
+            matches!(&token, TokenTree::Ident(i) if i == "async" || (after_async && i == "move"));
+    }
+    false
+}
+
+/// Wraps the `body` in a function like rustdoc does for doctests and returns statements to call it.
+///
+/// Like rustdoc, leading inner attributes apply to the function, leading `extern crate` items are moved before it,
+/// and `extern crate #lib;` is added if the snippet names the library crate `lib` without declaring it.
+/// `fn main` is called if defined; otherwise, the function returns a `Result` if the snippet ends with `(())` or uses `?`.
+fn doctest(body: TokenStream, n: u64, lib: Option<&str>) -> TokenStream {
+    let f = Ident::new(&format!("__include_file_doctest{n}"), Span::call_site());
+    let mut tokens: Vec<TokenTree> = body.into_iter().collect();
+
+    // Inner attributes like `#![allow(unused)]` must come first.
+    let mut attrs = TokenStream::new();
+    while let [TokenTree::Punct(pound), TokenTree::Punct(bang), TokenTree::Group(group), ..] =
+        tokens.as_slice()
+    {
+        if pound.as_char() != '#'
+            || bang.as_char() != '!'
+            || group.delimiter() != Delimiter::Bracket
+        {
+            break;
+        }
+        attrs.extend(tokens.drain(..3));
+    }
+
+    // Extern crates like `#[macro_use] extern crate serde;` may follow.
+    let mut externs = TokenStream::new();
+    let mut crates = Vec::new();
+    while let Some((len, name)) = extern_crate(&tokens) {
+        crates.push(name);
+        externs.extend(tokens.drain(..len));
+    }
+    if let Some(lib) = lib {
+        if !crates.iter().any(|name| name == lib) && names(tokens.iter().cloned().collect(), lib) {
+            let lib = Ident::new(lib, Span::call_site());
+            externs.extend(quote! {
+                #[allow(unused_extern_crates)]
+                extern crate #lib;
+            });
+        }
+    }
+
+    let has_main = tokens.windows(2).any(|pair| {
+        matches!(pair, [TokenTree::Ident(kw), TokenTree::Ident(name)] if kw == "fn" && name == "main")
+    });
+    let ends_with_unit = matches!(
+        tokens.last(),
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis && g.stream().to_string() == "()"
+    );
+    let body: TokenStream = tokens.into_iter().collect();
+
+    let call = if has_main {
+        quote! {
+            fn #f() -> ::std::process::ExitCode {
+                #attrs
+                #body
+                ::std::process::Termination::report(main())
+            }
+            ::std::assert!(#f() == ::std::process::ExitCode::SUCCESS, "main returned an error");
+        }
+    } else if ends_with_unit {
+        quote! {
+            fn #f() -> ::std::result::Result<(), impl ::std::fmt::Debug> {
+                #attrs
+                #body
+            }
+            #f().unwrap();
+        }
+    } else if uses_try(body.clone()) {
+        quote! {
+            #[allow(unreachable_code)]
+            fn #f() -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error>> {
+                #attrs
+                #body
+                ::std::result::Result::Ok(())
+            }
+            #f().unwrap();
+        }
+    } else {
+        quote! {
+            fn #f() {
+                #attrs
+                #body
+            }
+            #f();
+        }
+    };
+    quote! { #externs #call }
+}
+
+/// Returns the number of tokens in a leading `extern crate name;` item, including any outer attributes, and the `name`.
+fn extern_crate(tokens: &[TokenTree]) -> Option<(usize, String)> {
+    let mut i = 0;
+    while let [TokenTree::Punct(pound), TokenTree::Group(group), ..] = &tokens[i..] {
+        if pound.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        i += 2;
+    }
+    let [TokenTree::Ident(kw), TokenTree::Ident(krate), TokenTree::Ident(name), ..] = &tokens[i..]
+    else {
+        return None;
+    };
+    if kw != "extern" || krate != "crate" {
+        return None;
+    }
+    let end = tokens[i..]
+        .iter()
+        .position(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ';'))?;
+    Some((i + end + 1, name.to_string()))
+}
+
+/// Returns whether the `tokens`, including any nested groups, contain the identifier `name`.
+fn names(tokens: TokenStream, name: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == name,
+        TokenTree::Group(group) => names(group.stream(), name),
+        _ => false,
+    })
+}
+
+/// Parses a parameter like `scope` or `index = 1`, including keywords like `async` and lists like `replace = [..]`.
+fn parse_param(input: ParseStream) -> syn::Result<Meta> {
+    if input.peek(Token![async]) {
+        let token: Token![async] = input.parse()?;
+        return Ok(Meta::Path(Ident::new("async", token.span).into()));
+    }
+    if input.peek(syn::Ident) && input.peek2(Token![=]) && input.peek3(token::Bracket) {
+        // Array expressions require syn's "full" feature, so keep the list for the parameter to parse.
+        let path: Ident = input.parse()?;
+        let eq_token = input.parse()?;
+        let list: TokenTree = input.parse()?;
+        return Ok(Meta::NameValue(MetaNameValue {
+            path: path.into(),
+            eq_token,
+            value: Expr::Verbatim(list.into()),
+        }));
+    }
+    Meta::parse(input)
+}
+
+fn name_value<T: Parse>(meta: &Meta) -> syn::Result<T> {
+    match meta {
+        Meta::NameValue(MetaNameValue {
+            value: Expr::Lit(ExprLit { lit, .. }),
+            ..
+        }) => parse2(lit.to_token_stream()),
+        _ => Err(syn::Error::new(meta.span(), "expected a literal value")),
+    }
+}
+
+fn set_selector(
+    selector: &mut Option<(Selector, Span)>,
+    value: Selector,
+    span: Span,
+) -> syn::Result<()> {
+    if selector.is_some() {
+        return Err(syn::Error::new(
+            span,
+            r#"only one of "name", "index", or "lines" is allowed"#,
+        ));
+    }
+    *selector = Some((value, span));
+    Ok(())
+}
+
+fn set_mode(mode: &mut Option<(Mode, Span)>, value: Mode, span: Span) -> syn::Result<()> {
+    if mode.is_some() {
+        return Err(syn::Error::new(span, "only one mode is allowed"));
+    }
+    *mode = Some((value, span));
+    Ok(())
+}
+
 fn include_file<F>(item: TokenStream, f: F) -> syn::Result<TokenStream>
 where
-    F: FnOnce(&str, io::Lines<io::BufReader<fs::File>>) -> io::Result<(u32, Vec<String>)>,
+    F: 'static + Copy + FnOnce(io::Lines<io::BufReader<fs::File>>) -> io::Result<Vec<Block>>,
 {
-    let args: MarkdownArgs = parse2(item)?;
+    let config = manifest_dir()
+        .and_then(|dir| cache::config(&dir))
+        .map_err(|err| syn::Error::new(Span::call_site(), err))?;
+    let args = (|input: ParseStream| MarkdownArgs::parse_with(input, &config)).parse2(item)?;
     let root = match args.relative {
         #[cfg(span_locations)]
-        Some(span) => span.local_file(),
+        Some(span) => span.local_file().map_or(Root::Manifest, Root::Source),
         #[cfg(not(span_locations))]
         Some(span) => return Err(syn::Error::new(span, "requires rustc 1.88 or newer")),
-        None => None,
+        None => args.root.clone(),
+    };
+    let Snippet {
+        relative_path,
+        display_path,
+        start_line,
+        content,
+        blocks,
+        mut tracked,
+        ..
+    } = find_snippet(
+        &config,
+        &root,
+        &args.path,
+        &args.selector,
+        args.selector_span,
+        args.duplicates,
+        f,
+    )?;
+    if let Some(sha256) = &args.sha256 {
+        pin::check(sha256, &args.selector, &content)?;
+    }
+
+    // Blocks included with `duplicates = "all"` are not contiguous, so only the first line is known.
+    let contiguous = args.duplicates != Some(Duplicates::All);
+    // Statement lines are counted within the block as written, before any replacements change its lines.
+    let statement_lines = (contiguous && matches!(args.mode, Mode::Statements | Mode::Expr))
+        .then(|| statements::lines(&content));
+    let end_line = start_line + content.lines().count().saturating_sub(1) as u32;
+    let substitute_err = |span, (line, err): (usize, String)| {
+        syn::Error::new(
+            span,
+            format!("{err} at {display_path}:{}", start_line as usize + line),
+        )
+    };
+    if let Some(span) = args.substitute {
+        // Placeholders are checked within the block as written, before any replacements change its lines.
+        substitute::substitute(&content, |name| env::var(name).ok())
+            .map_err(|err| substitute_err(span, err))?;
+    }
+    let mut content = content;
+    for replacement in &args.replace {
+        content = replacement.apply(&content)?;
+    }
+    let content = match args.substitute {
+        Some(span) => {
+            let mut vars = Vec::new();
+            let content = substitute::substitute(&content, |name| {
+                vars.push(name.to_string());
+                env::var(name).ok()
+            })
+            .map_err(|err| substitute_err(span, err))?;
+
+            // Register the variables as dependencies so the crate is rebuilt when they change.
+            for var in vars {
+                tracked.extend(
+                    quote! { const _: ::std::option::Option<&str> = ::std::option_env!(#var); },
+                );
+            }
+            content
+        }
+        None => content,
     };
-    let (file, display_path) =
-        open(root, &args.path.value()).map_err(|err| syn::Error::new(args.path.span(), err))?;
-    let (start_line, content) = extract(file, &args.name.value(), f)
-        .map_err(|err| syn::Error::new(args.name.span(), err))?;
+    let mut body: TokenStream = content.parse()?;
+    manifest::record(&display_path, &args.selector, &blocks)?;
+
+    if args.mode == Mode::Items {
+        // Items are not executed, so there is nothing to guard.
+        tracked.extend(body);
+        return Ok(tracked);
+    }
 
     let n = INCLUDE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    if args.mode == Mode::Doctest {
+        // Like rustdoc, the library is not injected into its own tests.
+        let lib = config
+            .lib
+            .as_deref()
+            .filter(|lib| env::var("CARGO_CRATE_NAME").as_deref() != Ok(*lib));
+        body = doctest(body, n, lib);
+    }
     let guard_type = Ident::new(&format!("__IncludeFileGuard{n}"), Span::call_site());
     let guard_var = Ident::new(&format!("__include_file_guard{n}"), Span::call_site());
+    let mut first_line = start_line;
+    let mut marked = false;
+    if let Some(lines) = statement_lines {
+        // Track the line of each statement as it runs so a panic reports where within the snippet it occurred.
+        let marker = |line| {
+            let line = start_line + line as u32;
+            quote! { #guard_var.line.set(#line); }
+        };
+        if let Some(marked_body) = statements::mark(body.clone(), &lines, marker) {
+            first_line += lines[0].unwrap_or_default() as u32;
+            body = marked_body;
+            marked = true;
+        }
+    }
+    let end_line = if contiguous { end_line } else { start_line };
+    let lines = match end_line > start_line {
+        true => format!("{start_line}-{end_line}"),
+        false => start_line.to_string(),
+    };
+    // The statement is only reported if its line is known.
+    let message = match marked {
+        true => quote! {
+            ::std::format!(
+                "panicked in code included from {}:{} (statement at line {})",
+                self.file,
+                #lines,
+                line
+            )
+        },
+        false => quote! {
+            ::std::format!("panicked in code included from {}:{}", self.file, #lines)
+        },
+    };
+    let json_line = match marked {
+        true => quote! { line.to_string() },
+        false => quote! { "null" },
+    };
 
     // Compute the file expression for the guard based on whether `relative` was passed.
     // Use Location::caller().file() to resolve paths consistently with panic messages.
-    let file_expr: TokenStream = if args.relative.is_some() {
+    let file_expr: TokenStream = if !matches!(root, Root::Manifest | Root::Source(_)) {
+        // Path is relative to another root and already displayed relative to the workspace or as an absolute path.
+        quote! { ::std::string::String::from(#display_path) }
+    } else if args.relative.is_some() {
         // Path is relative to the source file.
         // Resolve against caller's directory and normalize.
-        let path_str = args.path.value();
+        let path_str = &relative_path;
         quote! {
             {
                 let __caller = ::std::panic::Location::caller().file();
@@ -354,30 +1241,96 @@ where
         }
     };
 
+    let json_string: TokenStream = include_str!("json.rs").parse()?;
     let guard = quote! {
         struct #guard_type {
             file: ::std::string::String,
-            line: u32,
+            line: ::std::cell::Cell<u32>,
         }
         impl ::std::ops::Drop for #guard_type {
             fn drop(&mut self) {
                 if ::std::thread::panicking() {
-                    ::std::eprintln!(
-                        "note: panicked in code included from {}:{}",
-                        self.file,
-                        self.line
-                    );
+                    let line = self.line.get();
+                    let message = #message;
+                    // Test reporters can opt into annotations or JSON lines associated with the included file.
+                    match ::std::env::var(#NOTE_FORMAT_VAR).as_deref() {
+                        ::std::result::Result::Ok("github") => {
+                            let escape = |s: &str, property: bool| {
+                                let s = s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+                                if property {
+                                    s.replace(':', "%3A").replace(',', "%2C")
+                                } else {
+                                    s
+                                }
+                            };
+                            ::std::eprintln!(
+                                "::error file={},line={},title=include-file::{}",
+                                escape(&self.file, true),
+                                line,
+                                escape(&message, false)
+                            );
+                        }
+                        ::std::result::Result::Ok("json") => {
+                            #json_string
+                            ::std::eprintln!(
+                                r#"{{"file":{},"start_line":{},"end_line":{},"line":{},"message":{}}}"#,
+                                json_string(&self.file),
+                                #start_line,
+                                #end_line,
+                                #json_line,
+                                json_string(&message)
+                            );
+                        }
+                        _ => ::std::eprintln!("note: {message}"),
+                    }
                 }
             }
         }
         let #guard_var = #guard_type {
             file: #file_expr,
-            line: #start_line,
+            line: ::std::cell::Cell::new(#first_line),
         };
     };
 
-    let body: TokenStream = content.parse()?;
-    let mut output = guard;
+    let mut output = tracked;
+    output.extend(guard);
+    if let Some(runtime) = &args.runtime {
+        // Propagate errors from `?` in statements to the calling function.
+        let propagate = args.mode == Mode::Statements && uses_try(body.clone());
+        let future = if propagate {
+            quote! {
+                async {
+                    #body
+                    #[allow(unreachable_code)]
+                    let __include_file_result: ::std::result::Result<
+                        (),
+                        ::std::boxed::Box<dyn ::std::error::Error>,
+                    > = ::std::result::Result::Ok(());
+                    __include_file_result
+                }
+            }
+        } else {
+            quote! { async { #body } }
+        };
+        let block_on = runtime.block_on(future)?;
+        body = match args.mode {
+            Mode::Expr => block_on,
+            _ if propagate => quote! { #block_on?; },
+            _ => quote! { #block_on; },
+        };
+    }
+
+    if args.mode == Mode::Expr {
+        // Bind the value of the body so the guard can be dropped before returning it.
+        let value_var = Ident::new(&format!("__include_file_value{n}"), Span::call_site());
+        output.extend(quote! {
+            let #value_var = { #body };
+            ::std::mem::drop(#guard_var);
+            #value_var
+        });
+        return Ok(TokenTree::Group(Group::new(Delimiter::Brace, output)).into());
+    }
+
     output.extend(body);
     // Explicitly drop the guard right after the included body so that, when
     // multiple macros are used in the same scope, prior guards are already gone
@@ -392,47 +1345,199 @@ where
     Ok(output)
 }
 
-fn open(root: Option<PathBuf>, path: &str) -> io::Result<(fs::File, String)> {
-    let manifest_dir: PathBuf = env::var("CARGO_MANIFEST_DIR")
-        .map_err(|_| io::Error::other("no manifest directory"))?
-        .into();
-    let root_dir = match root {
-        Some(ref src) => src
-            .parent()
-            .map(|dir| manifest_dir.join(dir))
-            .ok_or_else(|| io::Error::other("no source parent directory"))?,
-        None => manifest_dir.clone(),
-    };
-    let full_path = root_dir.join(path);
-    let file = fs::File::open(&full_path)?;
-    let display_path = {
-        // Canonicalize to resolve any `..` components; fall back to the
-        // unresolved paths if canonicalization fails (e.g., a race with
-        // deletion), which is acceptable since we already opened the file.
-        let canonical_full = fs::canonicalize(&full_path).unwrap_or_else(|_| full_path.clone());
-        let canonical_manifest =
-            fs::canonicalize(&manifest_dir).unwrap_or_else(|_| manifest_dir.clone());
-        let rel = canonical_full
-            .strip_prefix(&canonical_manifest)
-            .unwrap_or(std::path::Path::new(path));
-        rel.to_string_lossy().into_owned()
-    };
-    Ok((file, display_path))
+/// A snippet found by [`find_snippet`].
+struct Snippet {
+    /// The path of the file relative to the root directory.
+    relative_path: String,
+    /// The path of the file to display in panic messages.
+    display_path: String,
+    /// The 1-based line number where the snippet starts.
+    start_line: u32,
+    content: String,
+    /// The 1-based line number where each block included starts, and its content.
+    blocks: Vec<(u32, String)>,
+    /// The number of bytes of indentation stripped from each line of the first block.
+    indent: usize,
+    /// Constants that register every file searched as a dependency.
+    tracked: TokenStream,
 }
 
-fn extract<R, F>(buffer: R, name: &str, f: F) -> io::Result<(u32, String)>
+/// Searches every file matching `path` for the block selected by `selector`, which must be found in exactly one.
+fn find_snippet<F>(
+    config: &Config,
+    root: &Root,
+    path: &LitStr,
+    selector: &Selector,
+    selector_span: Span,
+    duplicates: Option<Duplicates>,
+    f: F,
+) -> syn::Result<Snippet>
 where
-    R: io::Read,
-    F: FnOnce(&str, io::Lines<io::BufReader<R>>) -> io::Result<(u32, Vec<String>)>,
+    F: 'static + Copy + FnOnce(io::Lines<io::BufReader<fs::File>>) -> io::Result<Vec<Block>>,
 {
-    let reader = io::BufReader::new(buffer);
-    let (start_line, lines) = f(name, reader.lines())?;
-    if lines.is_empty() {
+    let path_span = path.span();
+    let path = path.value();
+    let files = open_all(config, root, &path).map_err(|err| syn::Error::new(path_span, err))?;
+
+    let mut found = Vec::new();
+    // Register the variable as a dependency so setting it records blocks from crates already built.
+    let manifest_var = manifest::MANIFEST_VAR;
+    let mut tracked =
+        quote! { const _: ::std::option::Option<&str> = ::std::option_env!(#manifest_var); };
+    if let Root::Env(var) = root {
+        // Register the variable as a dependency so the crate is rebuilt when the directory changes.
+        tracked.extend(quote! { const _: ::std::option::Option<&str> = ::std::option_env!(#var); });
+    }
+    for OpenFile {
+        file,
+        path: canonical_path,
+        relative_path,
+        display_path,
+    } in files
+    {
+        // Register the file as a dependency so the crate is rebuilt when it changes.
+        let tracked_path = canonical_path.to_string_lossy();
+        tracked.extend(quote! { const _: &[u8] = ::std::include_bytes!(#tracked_path); });
+        match cache::blocks(file, &canonical_path, f).and_then(|blocks| {
+            let selected = select(selector, duplicates, &blocks, &display_path)?;
+            let line = selected[0].0;
+            let indent = blocks
+                .iter()
+                .find(|block| block.start_line <= line && line <= block.end_line())
+                .map_or(0, |block| block.indent);
+            Ok((selected, indent))
+        }) {
+            Ok((blocks, indent)) => found.push((relative_path, display_path, blocks, indent)),
+            Err(err) if is_glob(&path) && err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(syn::Error::new(selector_span, err)),
+        }
+    }
+    let (relative_path, display_path, blocks, indent) = match found.len() {
+        1 => found.swap_remove(0),
+        0 => {
+            return Err(syn::Error::new(
+                selector_span,
+                format!("{selector} not found in any file matching {path}"),
+            ))
+        }
+        _ => {
+            let locations: Vec<_> = found
+                .iter()
+                .map(|(_, display_path, blocks, _)| format!("{display_path}:{}", blocks[0].0))
+                .collect();
+            return Err(syn::Error::new(
+                selector_span,
+                format!("{selector} is ambiguous; found in {}", locations.join(", ")),
+            ));
+        }
+    };
+
+    let content: Vec<&str> = blocks.iter().map(|(_, content)| content.as_str()).collect();
+    Ok(Snippet {
+        relative_path,
+        display_path,
+        start_line: blocks[0].0,
+        content: content.join("\n"),
+        blocks,
+        indent,
+        tracked,
+    })
+}
+
+/// A file opened relative to a [`Root`].
+struct OpenFile {
+    file: fs::File,
+    /// The canonical path of the file.
+    path: PathBuf,
+    /// The path of the file relative to the root directory.
+    relative_path: String,
+    /// The path of the file to display in panic messages.
+    display_path: String,
+}
+
+/// Whether `path` is a glob pattern like `docs/**/*.md`.
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Opens `path` relative to `root`, or every file matching `path` sorted by path if it is a glob pattern.
+fn open_all(config: &Config, root: &Root, path: &str) -> io::Result<Vec<OpenFile>> {
+    if !is_glob(path) {
+        return Ok(vec![open(config, root, path)?]);
+    }
+
+    let dir = root.dir(&manifest_dir()?)?;
+    let pattern = Path::new(&glob::Pattern::escape(&dir.to_string_lossy())).join(path);
+    let mut files = Vec::new();
+    for entry in glob::glob(&pattern.to_string_lossy())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+    {
+        let full_path = entry.map_err(io::Error::from)?;
+        if full_path.is_file() {
+            let relative_path = full_path.strip_prefix(&dir).unwrap_or(&full_path);
+            files.push(open(config, root, &relative_path.to_string_lossy())?);
+        }
+    }
+    if files.is_empty() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
-            format!("code fence '{}' not found", name),
+            format!("no files match {path}"),
         ));
     }
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(files)
+}
 
-    Ok((start_line, lines.join("\n")))
+fn manifest_dir() -> io::Result<PathBuf> {
+    env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| io::Error::other("no manifest directory"))
+}
+
+/// Opens `path` relative to `root`, checking that it is allowed by `config`.
+fn open(config: &Config, root: &Root, path: &str) -> io::Result<OpenFile> {
+    let manifest_dir = manifest_dir()?;
+    let full_path = root.dir(&manifest_dir)?.join(path);
+    let file = fs::File::open(&full_path)?;
+    let canonical_full = if config.strict {
+        // Files may only be included from within the package or workspace, or the build script's OUT_DIR.
+        let canonical_full = fs::canonicalize(&full_path)?;
+        let mut allowed = vec![fs::canonicalize(workspace_dir(&manifest_dir)?)?];
+        if *root == Root::OutDir {
+            allowed.push(fs::canonicalize(root.dir(&manifest_dir)?)?);
+        }
+        config.check(&canonical_full, file.metadata()?.len(), &allowed)?;
+        canonical_full
+    } else {
+        // Canonicalize to resolve any `..` components; fall back to the
+        // unresolved paths if canonicalization fails (e.g., a race with
+        // deletion), which is acceptable since we already opened the file.
+        fs::canonicalize(&full_path).unwrap_or_else(|_| full_path.clone())
+    };
+    let display_path = match root {
+        Root::Manifest | Root::Source(_) => {
+            let canonical_manifest =
+                fs::canonicalize(&manifest_dir).unwrap_or_else(|_| manifest_dir.clone());
+            let rel = canonical_full
+                .strip_prefix(&canonical_manifest)
+                .unwrap_or(Path::new(path));
+            rel.to_string_lossy().into_owned()
+        }
+        // Panic messages show paths relative to the workspace root.
+        Root::Workspace => {
+            let workspace_dir = workspace_dir(&manifest_dir)?;
+            let canonical_workspace = fs::canonicalize(&workspace_dir).unwrap_or(workspace_dir);
+            let rel = canonical_full
+                .strip_prefix(&canonical_workspace)
+                .unwrap_or(&canonical_full);
+            rel.to_string_lossy().into_owned()
+        }
+        Root::OutDir | Root::Env(_) => canonical_full.to_string_lossy().into_owned(),
+    };
+    Ok(OpenFile {
+        file,
+        path: canonical_full,
+        relative_path: path.to_string(),
+        display_path,
+    })
 }
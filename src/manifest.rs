@@ -0,0 +1,196 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{manifest_dir, workspace_dir};
+use include_file_core::{sha256, Selector};
+use proc_macro2::Span;
+use std::{env, fs, io::Write, path::Path};
+
+include!("json.rs");
+
+/// The environment variable naming a JSON Lines file each macro expansion appends a record of the included block to.
+pub const MANIFEST_VAR: &str = "INCLUDE_FILE_MANIFEST";
+
+/// A code block included by a macro.
+pub struct Record<'a> {
+    /// The path of the document displayed in panic messages.
+    pub path: &'a str,
+    pub name: Option<&'a str>,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub content: &'a str,
+}
+
+/// Appends a record of each of the `blocks` included from `path` by `selector`, given their start line and content.
+///
+/// Call this only once the code including them was built so failed expansions are not recorded.
+pub fn record(path: &str, selector: &Selector, blocks: &[(u32, String)]) -> syn::Result<()> {
+    for record in records(path, selector, blocks) {
+        append(&record).map_err(|err| {
+            syn::Error::new(
+                Span::call_site(),
+                format!("failed to write {MANIFEST_VAR}: {err}"),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns a record for each of the `blocks` included from `path` by `selector`.
+fn records<'a>(
+    path: &'a str,
+    selector: &'a Selector,
+    blocks: &'a [(u32, String)],
+) -> impl Iterator<Item = Record<'a>> {
+    let name = match selector {
+        Selector::Name(name) => Some(name.as_str()),
+        _ => None,
+    };
+    blocks.iter().map(move |(start_line, content)| Record {
+        path,
+        name,
+        start_line: *start_line,
+        end_line: start_line + content.lines().count().saturating_sub(1) as u32,
+        content,
+    })
+}
+
+/// Appends `record` to the file named by [`MANIFEST_VAR`], if set, relative to the workspace root directory.
+fn append(record: &Record) -> std::io::Result<()> {
+    let Some(manifest) = env::var_os(MANIFEST_VAR) else {
+        return Ok(());
+    };
+    let path = workspace_dir(&manifest_dir()?)?.join(manifest);
+    write(&path, record)
+}
+
+/// Appends `record` as a JSON line to the file at `path`.
+fn write(path: &Path, record: &Record) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let (file, line) = caller();
+    let string = |value: Option<&str>| value.map_or_else(|| "null".into(), json_string);
+    let line = format!(
+        "{{\"crate\":{},\"file\":{},\"line\":{},\"path\":{},\"name\":{},\"start_line\":{},\"end_line\":{},\"sha256\":{}}}\n",
+        string(env::var("CARGO_PKG_NAME").ok().as_deref()),
+        string(file.as_deref()),
+        line.map_or_else(|| "null".into(), |line| line.to_string()),
+        json_string(record.path),
+        string(record.name),
+        record.start_line,
+        record.end_line,
+        json_string(&sha256(record.content)),
+    );
+
+    // Crates compile concurrently, so write each record at once to the end of the file.
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Returns the source file and line of the macro call, if known.
+#[cfg(span_locations)]
+fn caller() -> (Option<String>, Option<usize>) {
+    let span = proc_macro2::Span::call_site();
+    let file = span
+        .local_file()
+        .map(|file| file.to_string_lossy().into_owned());
+    (file, Some(span.start().line))
+}
+
+#[cfg(not(span_locations))]
+fn caller() -> (Option<String>, Option<usize>) {
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, records, write, Record};
+    use include_file_core::{sha256, Selector};
+    use std::{env, fs};
+
+    #[test]
+    fn records_each_block() {
+        let selector = Selector::Name("example".into());
+        let blocks = vec![
+            (3, "let first = 1;".to_string()),
+            (10, "let last = first;\nassert_eq!(last, 1);".to_string()),
+        ];
+        let actual: Vec<_> = records("README.md", &selector, &blocks)
+            .map(|record| {
+                (
+                    record.name,
+                    record.start_line,
+                    record.end_line,
+                    record.content,
+                )
+            })
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                (Some("example"), 3, 3, "let first = 1;"),
+                (
+                    Some("example"),
+                    10,
+                    11,
+                    "let last = first;\nassert_eq!(last, 1);"
+                ),
+            ]
+        );
+
+        let selector = Selector::Index(1);
+        let record = records("README.md", &selector, &blocks[..1])
+            .next()
+            .expect("expected record");
+        assert_eq!(record.name, None);
+    }
+
+    #[test]
+    fn write_records() {
+        let path = env::temp_dir().join(format!(
+            "include-file-manifest-{}/manifest.jsonl",
+            std::process::id()
+        ));
+        for name in [Some("example"), None] {
+            let record = Record {
+                path: "README.md",
+                name,
+                start_line: 20,
+                end_line: 21,
+                content: "let x = 1;",
+            };
+            write(&path, &record).expect("expected record");
+        }
+
+        let content = fs::read_to_string(&path).expect("read manifest");
+        let records: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("expected JSON line"))
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["path"], "README.md");
+        assert_eq!(records[0]["name"], "example");
+        assert_eq!(records[0]["start_line"], 20);
+        assert_eq!(records[0]["end_line"], 21);
+        assert_eq!(records[0]["sha256"], sha256("let x = 1;"));
+        assert_eq!(records[1]["name"], serde_json::Value::Null);
+
+        fs::remove_dir_all(path.parent().expect("parent")).ok();
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        let s = "\"C:\\path\"\n\r\t\u{1b}[0m\0é";
+        let json = json_string(s);
+        assert_eq!(json, r#""\"C:\\path\"\n\r\t\u001b[0m\u0000é""#);
+        assert_eq!(
+            serde_json::from_str::<String>(&json).expect("expected JSON string"),
+            s
+        );
+    }
+}
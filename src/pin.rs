@@ -0,0 +1,69 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::name_value;
+use include_file_core::{sha256, Selector};
+use syn::{LitStr, Meta};
+
+/// Parses `sha256 = "..."` with a hash of 64 hexadecimal digits.
+pub fn from_meta(meta: &Meta) -> syn::Result<LitStr> {
+    let lit: LitStr = name_value(meta)?;
+    let value = lit.value();
+    if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(syn::Error::new(
+            lit.span(),
+            "expected a SHA-256 hash of 64 hexadecimal digits",
+        ));
+    }
+    Ok(lit)
+}
+
+/// Fails if the SHA-256 hash of the `content` selected by `selector` does not match `pin`.
+pub fn check(pin: &LitStr, selector: &Selector, content: &str) -> syn::Result<()> {
+    let actual = sha256(content);
+    if pin.value().eq_ignore_ascii_case(&actual) {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        pin.span(),
+        format!(r#"{selector} changed; review it and update to sha256 = "{actual}""#),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, from_meta};
+    use include_file_core::Selector;
+    use syn::{parse_quote, LitStr, Meta};
+
+    const HASH: &str = "aad7378dfeb851703927a534da47d0d02be7f54cf8c1751a37734d515811dafe";
+
+    #[test]
+    fn from_meta_hash() {
+        let meta: Meta = parse_quote! { sha256 = "AAD7378DFEB851703927A534DA47D0D02BE7F54CF8C1751A37734D515811DAFE" };
+        from_meta(&meta).expect("expected hash");
+
+        for meta in [
+            parse_quote! { sha256 = "aad7378d" },
+            parse_quote! { sha256 = "zad7378dfeb851703927a534da47d0d02be7f54cf8c1751a37734d515811dafe" },
+            parse_quote! { sha256 },
+        ] {
+            assert!(from_meta(&meta).is_err(), "expected invalid hash");
+        }
+    }
+
+    #[test]
+    fn check_hash() {
+        let selector = Selector::Name("example".into());
+        let pin = LitStr::new(HASH, proc_macro2::Span::call_site());
+        check(&pin, &selector, "let x = 1;").expect("expected matching hash");
+
+        let err = check(&pin, &selector, "let x = 2;").expect_err("expected changed hash");
+        assert!(
+            err.to_string()
+                .starts_with("code fence 'example' changed; review it and update to sha256 = \""),
+            "{err}"
+        );
+        assert!(!err.to_string().contains(HASH), "{err}");
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use proc_macro2::Span;
+use regex_lite::Regex;
+use std::fmt;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Expr, Ident, LitStr, Meta, MetaNameValue, Token,
+};
+
+/// Replaces text in a snippet before it is tokenized.
+pub struct Replacement {
+    pattern: Pattern,
+    replacement: String,
+    span: Span,
+}
+
+enum Pattern {
+    Literal(String),
+    Regex(Regex),
+}
+
+impl Replacement {
+    /// Parses `replace = [("from", "to"), (regex("pattern"), "to")]`.
+    pub fn from_meta(meta: &Meta) -> syn::Result<Vec<Self>> {
+        const EXPECTED: &str =
+            r#"expected a list like [("from", "to")] or [(regex("pattern"), "to")]"#;
+        let Meta::NameValue(MetaNameValue {
+            value: Expr::Verbatim(tokens),
+            ..
+        }) = meta
+        else {
+            return Err(syn::Error::new(meta.span(), EXPECTED));
+        };
+        let parse = |input: ParseStream| {
+            let content;
+            syn::bracketed!(content in input);
+            Punctuated::<Self, Token![,]>::parse_terminated(&content)
+        };
+        let replacements = parse.parse2(tokens.clone())?;
+        if replacements.is_empty() {
+            return Err(syn::Error::new(meta.span(), EXPECTED));
+        }
+        Ok(replacements.into_iter().collect())
+    }
+
+    /// Replaces every match of the pattern in `content`, failing if there are none so stale replacements are caught.
+    pub fn apply(&self, content: &str) -> syn::Result<String> {
+        let replaced = match &self.pattern {
+            Pattern::Literal(from) if content.contains(from.as_str()) => {
+                Some(content.replace(from.as_str(), &self.replacement))
+            }
+            Pattern::Regex(regex) if regex.is_match(content) => Some(
+                regex
+                    .replace_all(content, self.replacement.as_str())
+                    .into_owned(),
+            ),
+            _ => None,
+        };
+        replaced.ok_or_else(|| {
+            syn::Error::new(
+                self.span,
+                format!("replacement pattern {} matched nothing", self.pattern),
+            )
+        })
+    }
+}
+
+impl Parse for Replacement {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let (pattern, span) = if content.peek(Ident) {
+            let ident: Ident = content.parse()?;
+            if ident != "regex" {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    r#"expected a string or regex("pattern")"#,
+                ));
+            }
+            let args;
+            parenthesized!(args in content);
+            let lit: LitStr = args.parse()?;
+            let regex = Regex::new(&lit.value()).map_err(|err| syn::Error::new(lit.span(), err))?;
+            (Pattern::Regex(regex), lit.span())
+        } else {
+            let lit: LitStr = content.parse()?;
+            if lit.value().is_empty() {
+                return Err(syn::Error::new(lit.span(), "replacement pattern is empty"));
+            }
+            (Pattern::Literal(lit.value()), lit.span())
+        };
+        content.parse::<Token![,]>()?;
+        let replacement: LitStr = content.parse()?;
+        if content.peek(Token![,]) {
+            content.parse::<Token![,]>()?;
+        }
+        if !content.is_empty() {
+            return Err(content.error("unexpected token"));
+        }
+        Ok(Self {
+            pattern,
+            replacement: replacement.value(),
+            span,
+        })
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(from) => write!(f, "{from:?}"),
+            Self::Regex(regex) => write!(f, "regex({:?})", regex.as_str()),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use super::{
+    cache, manifest_dir, name_value, open, parse_param, workspace_dir, Config, OpenFile, Root,
+};
+use include_file_core::markdown;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+use syn::{
+    parse::{ParseStream, Parser},
+    spanned::Spanned,
+    LitInt, LitStr, Token,
+};
+
+struct SectionArgs {
+    path: LitStr,
+    title: Option<LitStr>,
+    demote: usize,
+    drop_badges: bool,
+    base_url: Option<String>,
+    root: Root,
+}
+
+impl SectionArgs {
+    /// Parses arguments, using defaults from `config` for any parameters not passed.
+    fn parse_with(input: ParseStream, config: &Config) -> syn::Result<Self> {
+        let path: LitStr = input.parse().map_err(|err| {
+            syn::Error::new(err.span(), r#"missing required string parameter ("path")"#)
+        })?;
+
+        // The heading may be omitted to include the whole file.
+        let mut title = None;
+        if input.peek(Token![,]) && input.peek2(LitStr) {
+            input.parse::<Token![,]>()?;
+            title = Some(input.parse()?);
+        }
+
+        let mut demote = 0;
+        let mut drop_badges = false;
+        let mut base_url = None;
+        let mut root = None;
+        if input.parse::<Token![,]>().is_ok() {
+            let params = input.parse_terminated(parse_param, Token![,])?;
+            for param in params {
+                if param.path().is_ident("demote") {
+                    let lit: LitInt = name_value(&param)?;
+                    demote = lit.base10_parse()?;
+                    if !(1..=5).contains(&demote) {
+                        return Err(syn::Error::new(lit.span(), "demote must be from 1 to 5"));
+                    }
+                } else if param.path().is_ident("drop_badges") {
+                    drop_badges = true;
+                } else if param.path().is_ident("base_url") {
+                    let lit: LitStr = name_value(&param)?;
+                    base_url = Some(lit.value().trim_end_matches('/').to_string());
+                } else if param.path().is_ident("root") {
+                    root = Some(Root::from_meta(&param)?);
+                } else {
+                    return Err(syn::Error::new(param.span(), "unsupported parameter"));
+                }
+            }
+        } else if !input.is_empty() {
+            return Err(syn::Error::new(input.span(), "unexpected token"));
+        }
+
+        Ok(Self {
+            path,
+            title,
+            demote,
+            drop_badges,
+            base_url,
+            root: root.unwrap_or_else(|| config.root.clone()),
+        })
+    }
+}
+
+pub fn include_markdown_section(item: TokenStream) -> syn::Result<TokenStream> {
+    include_markdown(item, true)
+}
+
+pub fn include_doc_as_markdown(item: TokenStream) -> syn::Result<TokenStream> {
+    include_markdown(item, false)
+}
+
+/// Includes a file, or the section under a heading if `required` or passed, converted to Markdown.
+fn include_markdown(item: TokenStream, required: bool) -> syn::Result<TokenStream> {
+    let manifest_dir = manifest_dir().map_err(|err| syn::Error::new(Span::call_site(), err))?;
+    let config =
+        cache::config(&manifest_dir).map_err(|err| syn::Error::new(Span::call_site(), err))?;
+    let args = (|input: ParseStream| SectionArgs::parse_with(input, &config)).parse2(item)?;
+    if required && args.title.is_none() {
+        return Err(syn::Error::new(
+            args.path.span(),
+            r#"missing required string parameter ("heading")"#,
+        ));
+    }
+
+    let path = args.path.value();
+    let format = config
+        .format(&path)
+        .map_err(|err| syn::Error::new(args.path.span(), err))?;
+    let OpenFile {
+        file,
+        path: canonical_path,
+        ..
+    } = open(&config, &args.root, &path).map_err(|err| syn::Error::new(args.path.span(), err))?;
+    let mut lines = match &args.title {
+        Some(title) => format
+            .section(file, &title.value())
+            .map_err(|err| syn::Error::new(title.span(), err))?,
+        None => format
+            .to_markdown(file)
+            .map_err(|err| syn::Error::new(args.path.span(), err))?,
+    };
+
+    if args.demote > 0 {
+        lines = markdown::demote(&lines, args.demote);
+    }
+    if args.drop_badges {
+        lines = markdown::drop_badges(&lines);
+    }
+    if let Some(base_url) = &args.base_url {
+        // Links are relative to the directory containing the file, which is relative to the workspace root.
+        let dir = canonical_path.parent().unwrap_or(Path::new(""));
+        let workspace_dir = workspace_dir(&manifest_dir)
+            .and_then(fs::canonicalize)
+            .map_err(|err| syn::Error::new(Span::call_site(), err))?;
+        let dir = dir.strip_prefix(&workspace_dir).map_err(|_| {
+            syn::Error::new(
+                args.path.span(),
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "base_url requires a file within the workspace",
+                ),
+            )
+        })?;
+        lines = markdown::rewrite_links(&lines, |target| absolute_url(base_url, dir, target));
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+
+    // Unlike other macros, the file cannot be tracked because attributes like `#![doc = ..]` require a literal.
+    Ok(quote! { #content })
+}
+
+/// Returns `target` relative to `dir` as a URL under `base_url`, or `None` if `target` is already absolute or an anchor.
+fn absolute_url(base_url: &str, dir: &Path, target: &str) -> Option<String> {
+    if target.is_empty() || target.starts_with('#') || target.contains(':') {
+        return None;
+    }
+    let path = match target.strip_prefix('/') {
+        Some(target) => PathBuf::from(target),
+        None => dir.join(target),
+    };
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                parts.pop();
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            _ => {}
+        }
+    }
+    Some(format!("{base_url}/{}", parts.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{absolute_url, include_markdown_section};
+    use quote::quote;
+    use std::path::Path;
+
+    #[test]
+    fn absolute_url_relative() {
+        let base_url = "https://github.com/heaths/include-file/blob/main";
+        assert_eq!(
+            absolute_url(base_url, Path::new(""), "LICENSE.txt").as_deref(),
+            Some("https://github.com/heaths/include-file/blob/main/LICENSE.txt")
+        );
+        assert_eq!(
+            absolute_url(base_url, Path::new("tests"), "../LICENSE.txt#mit").as_deref(),
+            Some("https://github.com/heaths/include-file/blob/main/LICENSE.txt#mit")
+        );
+        assert_eq!(
+            absolute_url(base_url, Path::new("tests"), "/src/lib.rs").as_deref(),
+            Some("https://github.com/heaths/include-file/blob/main/src/lib.rs")
+        );
+        assert_eq!(absolute_url(base_url, Path::new(""), "#usage"), None);
+        assert_eq!(
+            absolute_url(base_url, Path::new(""), "https://docs.rs"),
+            None
+        );
+        assert_eq!(absolute_url(base_url, Path::new(""), "mailto:a@b.c"), None);
+    }
+
+    #[test]
+    fn include_section_markdown() {
+        let tokens = quote! {
+            "README.md",
+            "License",
+            demote = 1,
+            base_url = "https://github.com/heaths/include-file/blob/main/",
+        };
+        let actual = include_markdown_section(tokens).expect("expected section");
+        let expected = "### License\n\nLicensed under the [MIT](https://github.com/heaths/include-file/blob/main/LICENSE.txt) license.\n";
+        assert_eq!(actual.to_string(), quote! { #expected }.to_string());
+    }
+
+    #[cfg(feature = "asciidoc")]
+    #[test]
+    fn include_section_asciidoc() {
+        let tokens = quote! { "tests/README.adoc", "License" };
+        let actual = include_markdown_section(tokens).expect("expected section");
+        let expected = "## License\n\nLicensed under the [MIT](../LICENSE.txt) license.\n";
+        assert_eq!(actual.to_string(), quote! { #expected }.to_string());
+    }
+
+    #[test]
+    fn include_section_err() {
+        for tokens in [
+            quote! { "README.md" },
+            quote! { "README.md", "Missing" },
+            quote! { "README.md", "License", demote = 0 },
+            quote! { "README.md", "License", scope },
+        ] {
+            include_markdown_section(tokens).expect_err("expected error");
+        }
+    }
+
+    #[cfg(feature = "textile")]
+    #[test]
+    fn include_doc_textile() {
+        let tokens = quote! { "tests/README.textile" };
+        let actual = super::include_doc_as_markdown(tokens).expect("expected Markdown");
+        let actual: String = syn::parse2::<syn::LitStr>(actual)
+            .expect("expected literal")
+            .value();
+        assert!(
+            actual.starts_with("# Macros for including file content\n"),
+            "{actual}"
+        );
+        assert!(actual.contains("\n```rust\n"), "{actual}");
+
+        let tokens = quote! { "tests/README.textile", "Missing" };
+        super::include_doc_as_markdown(tokens).expect_err("expected missing section");
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Returns the 0-based line within `content` of the first statement followed by the line of the statement
+/// following each `;` outside any brackets, or `None` if there is no statement.
+///
+/// This is a minimal lexer that skips comments, strings, and character literals so the count of `;`
+/// matches the punctuation in the parsed [`TokenStream`].
+pub fn lines(content: &str) -> Vec<Option<usize>> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut lines = vec![None];
+    let mut line = 0;
+    let mut depth = 0usize;
+    // Whether the start of `content` or the last `;` is still waiting for the next statement to start.
+    let mut pending = true;
+    let mut i = 0;
+
+    // Advances past `n` characters, counting new lines.
+    let skip = |i: &mut usize, line: &mut usize, n: usize| {
+        for c in &chars[*i..(*i + n).min(chars.len())] {
+            if *c == '\n' {
+                *line += 1;
+            }
+        }
+        *i += n;
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            skip(&mut i, &mut line, 1);
+            continue;
+        }
+
+        // Comments.
+        if c == '/' && next == Some('/') {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .unwrap_or(chars.len() - i);
+            i += end;
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            let mut nested = 0;
+            let mut j = i;
+            while j < chars.len() {
+                if chars[j] == '/' && chars.get(j + 1) == Some(&'*') {
+                    nested += 1;
+                    j += 2;
+                } else if chars[j] == '*' && chars.get(j + 1) == Some(&'/') {
+                    nested -= 1;
+                    j += 2;
+                    if nested == 0 {
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            let len = j - i;
+            skip(&mut i, &mut line, len);
+            continue;
+        }
+
+        if pending {
+            if let Some(last) = lines.last_mut() {
+                *last = Some(line);
+            }
+            pending = false;
+        }
+
+        match c {
+            '"' => {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '"' {
+                    j += if chars[j] == '\\' { 2 } else { 1 };
+                }
+                let len = j + 1 - i;
+                skip(&mut i, &mut line, len);
+            }
+            'r' | 'b' | 'c' if raw_string_len(&chars[i..]).is_some() => {
+                let len = raw_string_len(&chars[i..]).unwrap_or(1);
+                skip(&mut i, &mut line, len);
+            }
+            '\'' => {
+                // A character literal like 'a' or '\n', otherwise a lifetime or label.
+                let len = if next == Some('\\') {
+                    chars[i + 2..]
+                        .iter()
+                        .position(|c| *c == '\'')
+                        .map_or(1, |end| end + 3)
+                } else if chars.get(i + 2) == Some(&'\'') {
+                    3
+                } else {
+                    1
+                };
+                skip(&mut i, &mut line, len);
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                i += 1;
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            ';' if depth == 0 => {
+                lines.push(None);
+                pending = true;
+                i += 1;
+            }
+            _ if c.is_alphanumeric() || c == '_' => {
+                // Skip whole identifiers so a trailing `r` or `b` is not mistaken for a raw string prefix.
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| !c.is_alphanumeric() && *c != '_')
+                    .unwrap_or(chars.len() - i);
+                i += end;
+            }
+            _ => i += 1,
+        }
+    }
+    lines
+}
+
+/// Returns the length of a raw string literal like `r#"..."#`, `br"..."`, or `cr"..."` at the start of `chars`.
+fn raw_string_len(chars: &[char]) -> Option<usize> {
+    let start = match chars {
+        ['b' | 'c', 'r', ..] => 2,
+        ['r', ..] => 1,
+        _ => return None,
+    };
+    let hashes = chars[start..].iter().take_while(|c| **c == '#').count();
+    if chars.get(start + hashes) != Some(&'"') {
+        return None;
+    }
+    let body = start + hashes + 1;
+    (body..chars.len())
+        .find(|&j| chars[j] == '"' && chars[j + 1..].iter().take(hashes).all(|c| *c == '#'))
+        .filter(|&j| j + hashes < chars.len())
+        .map(|j| j + 1 + hashes)
+}
+
+/// Inserts `marker(line)` after each `;` outside any brackets in `body` before the statement starting on `line`.
+///
+/// Returns `None` if `lines` from [`lines`] has no statement or does not match the punctuation in `body`.
+pub fn mark(
+    body: TokenStream,
+    lines: &[Option<usize>],
+    marker: impl Fn(usize) -> TokenStream,
+) -> Option<TokenStream> {
+    let is_semi = |tt: &TokenTree| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == ';');
+    if lines.first().copied().flatten().is_none()
+        || body.clone().into_iter().filter(is_semi).count() + 1 != lines.len()
+    {
+        return None;
+    }
+
+    let mut output = TokenStream::new();
+    let mut lines = lines.iter().skip(1);
+    for tt in body {
+        let semi = is_semi(&tt);
+        output.extend([tt]);
+        if semi {
+            if let Some(Some(line)) = lines.next() {
+                output.extend(marker(*line));
+            }
+        }
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lines, mark};
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    #[test]
+    fn lines_statements() {
+        let content = r##"// Leading comment.
+let a = 1; // ;
+let s = "a;\"b";
+let r = r#"c;"# ;
+/* ; /* ; */ */
+let c = ';';
+let v = vec![1; 2];
+fn f<'a>(x: &'a str) -> &'a str {
+    x;
+    x
+}
+let x = f("x");
+
+x"##;
+        assert_eq!(
+            lines(content),
+            vec![
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(5),
+                Some(6),
+                Some(7),
+                Some(13)
+            ]
+        );
+        assert_eq!(lines("let a = 1;"), vec![Some(0), None]);
+        assert_eq!(lines("// Empty."), vec![None]);
+    }
+
+    #[test]
+    fn mark_statements() {
+        let content = "let a = 1;\nif a == 1 {\n    b();\n}\nc();\n";
+        let body: TokenStream = content.parse().expect("expected tokens");
+        let actual = mark(body, &lines(content), |line| {
+            let line = line as u32;
+            quote! { mark(#line); }
+        })
+        .expect("expected marked statements");
+        let expected = quote! {
+            let a = 1; mark(1u32);
+            if a == 1 { b(); }
+            c();
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn mark_mismatch() {
+        // Lines counted before a replacement added a statement no longer match the body.
+        let body: TokenStream = "let a = 1; let b = 2;".parse().expect("expected tokens");
+        assert!(mark(body, &lines("let a = 1;"), |_| quote! {}).is_none());
+
+        let body = TokenStream::new();
+        assert!(mark(body, &lines("// Empty."), |_| quote! {}).is_none());
+    }
+}
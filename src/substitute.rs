@@ -0,0 +1,124 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+/// Replaces `{{version}}`, `{{crate_name}}`, and `{{env:VAR}}` placeholders in `content`.
+///
+/// Values are read from environment variables with `var`: `CARGO_PKG_VERSION` for `{{version}}`,
+/// `CARGO_PKG_NAME` with `-` replaced by `_` for `{{crate_name}}`, and `VAR` for `{{env:VAR}}`.
+/// Write `{{{{` for a literal `{{`, e.g. `println!("{{{{name}}")`; an empty `{{}}` is left as-is.
+/// Returns the 0-based line number and description of the first unknown or unset placeholder.
+pub fn substitute(
+    content: &str,
+    mut var: impl FnMut(&str) -> Option<String>,
+) -> Result<String, (usize, String)> {
+    let mut output = String::with_capacity(content.len());
+    for (i, line) in content.split('\n').enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            if rest[start..].starts_with("{{{{") {
+                output.push_str(&rest[..start + 2]);
+                rest = &rest[start + 4..];
+                continue;
+            }
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let end = start + len + 4;
+            let placeholder = &rest[start..end];
+            if rest[start + 2..end - 2].trim().is_empty() {
+                output.push_str(&rest[..end]);
+                rest = &rest[end..];
+                continue;
+            }
+            let (name, value) = match rest[start + 2..end - 2].trim() {
+                "version" => ("CARGO_PKG_VERSION", var("CARGO_PKG_VERSION")),
+                "crate_name" => (
+                    "CARGO_PKG_NAME",
+                    var("CARGO_PKG_NAME").map(|name| name.replace('-', "_")),
+                ),
+                name => match name.strip_prefix("env:").map(str::trim) {
+                    Some(name) if !name.is_empty() => (name, var(name)),
+                    _ => return Err((i, format!("unknown placeholder {placeholder}"))),
+                },
+            };
+            let value = value.ok_or_else(|| {
+                (
+                    i,
+                    format!("environment variable {name} is not set for placeholder {placeholder}"),
+                )
+            })?;
+            output.push_str(&rest[..start]);
+            output.push_str(&value);
+            rest = &rest[end..];
+        }
+        output.push_str(rest);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::substitute;
+
+    fn var(name: &str) -> Option<String> {
+        match name {
+            "CARGO_PKG_VERSION" => Some("1.2.3".into()),
+            "CARGO_PKG_NAME" => Some("my-crate".into()),
+            "API_URL" => Some("http://127.0.0.1:8080".into()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn substitute_placeholders() {
+        assert_eq!(
+            substitute(
+                "my-crate = \"{{version}}\"\nuse {{ crate_name }}::Client;\nlet url = \"{{env:API_URL}}\";",
+                var
+            ),
+            Ok("my-crate = \"1.2.3\"\nuse my_crate::Client;\nlet url = \"http://127.0.0.1:8080\";".into())
+        );
+        assert_eq!(
+            substitute("let s = \"{{ unclosed\";", var),
+            Ok("let s = \"{{ unclosed\";".into())
+        );
+    }
+
+    #[test]
+    fn substitute_escapes() {
+        assert_eq!(
+            substitute("println!(\"{{}} {{{{name}} {{version}}\");", var),
+            Ok("println!(\"{{}} {{name}} 1.2.3\");".into())
+        );
+        assert_eq!(
+            substitute("let s = \"{{{{{{crate_name}}\";", var),
+            Ok("let s = \"{{my_crate\";".into())
+        );
+    }
+
+    #[test]
+    fn substitute_unknown_err() {
+        assert_eq!(
+            substitute("let x = 1;\nlet v = \"{{vesion}}\";", var),
+            Err((1, "unknown placeholder {{vesion}}".into()))
+        );
+        assert_eq!(
+            substitute("{{env:}}", var),
+            Err((0, "unknown placeholder {{env:}}".into()))
+        );
+    }
+
+    #[test]
+    fn substitute_unset_err() {
+        assert_eq!(
+            substitute("let url = \"{{env:MISSING}}\";", var),
+            Err((
+                0,
+                "environment variable MISSING is not set for placeholder {{env:MISSING}}".into()
+            ))
+        );
+    }
+}
@@ -1,17 +1,25 @@
 // Copyright 2025 Heath Stewart.
 // Licensed under the MIT License. See LICENSE.txt in the project root for license information.
 
-use super::{include_file, open, MarkdownArgs};
+use super::{
+    doctest, include_file, open, uses_try, workspace_dir, Block, Config, MarkdownArgs, Mode,
+    OpenFile, Root, Runtime, Selector,
+};
 use proc_macro2::{Delimiter, TokenStream, TokenTree};
 use quote::quote;
-use std::{io, path::PathBuf};
-use syn::parse2;
+use std::{env, io, path::PathBuf};
+use syn::{
+    parse::{ParseStream, Parser},
+    parse2,
+};
 
-fn collect<R: io::Read>(
-    _name: &str,
-    _iter: io::Lines<io::BufReader<R>>,
-) -> io::Result<(u32, Vec<String>)> {
-    Ok((1, vec![r#"println!("example");"#.into()]))
+fn collect<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    Ok(vec![Block {
+        names: vec!["example".into()],
+        start_line: 1,
+        lines: vec![r#"println!("example");"#.into()],
+        ..Default::default()
+    }])
 }
 
 #[test]
@@ -21,7 +29,7 @@ fn parse_two_args() {
 
     let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
     assert_eq!(args.path.value(), "README.md");
-    assert_eq!(args.name.value(), "example");
+    assert_eq!(args.selector, Selector::Name("example".into()));
 }
 
 #[test]
@@ -59,7 +67,7 @@ fn parse_scope_param() {
     let tokens = quote! { "README.md", "example", scope };
     let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
     assert_eq!(args.path.value(), "README.md");
-    assert_eq!(args.name.value(), "example");
+    assert_eq!(args.selector, Selector::Name("example".into()));
     assert!(args.scope.is_some());
     assert!(args.relative.is_none());
 }
@@ -69,7 +77,7 @@ fn parse_relative_param() {
     let tokens = quote! { "README.md", "example", relative };
     let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
     assert_eq!(args.path.value(), "README.md");
-    assert_eq!(args.name.value(), "example");
+    assert_eq!(args.selector, Selector::Name("example".into()));
     assert!(args.scope.is_none());
     assert!(args.relative.is_some());
 }
@@ -79,7 +87,7 @@ fn parse_both_params() {
     let tokens = quote! { "README.md", "example", scope, relative };
     let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
     assert_eq!(args.path.value(), "README.md");
-    assert_eq!(args.name.value(), "example");
+    assert_eq!(args.selector, Selector::Name("example".into()));
     assert!(args.scope.is_some());
     assert!(args.relative.is_some());
 }
@@ -89,7 +97,7 @@ fn parse_both_params_reverse_order() {
     let tokens = quote! { "README.md", "example", relative, scope };
     let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
     assert_eq!(args.path.value(), "README.md");
-    assert_eq!(args.name.value(), "example");
+    assert_eq!(args.selector, Selector::Name("example".into()));
     assert!(args.scope.is_some());
     assert!(args.relative.is_some());
 }
@@ -136,6 +144,694 @@ fn parse_token_without_comma_err() {
     include_file(tokens, collect).expect_err("expected unexpected token error");
 }
 
+#[test]
+fn parse_index_param() {
+    let tokens = quote! { "README.md", index = 3 };
+    include_file(tokens.clone(), collect).expect_err("expected out of range error");
+
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.path.value(), "README.md");
+    assert_eq!(args.selector, Selector::Index(3));
+}
+
+#[test]
+fn parse_index_param_with_scope() {
+    let tokens = quote! { "README.md", index = 1, scope };
+    include_file(tokens.clone(), collect).expect("expected TokenStream");
+
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.selector, Selector::Index(1));
+    assert!(args.scope.is_some());
+}
+
+#[test]
+fn parse_index_zero_err() {
+    let tokens = quote! { "README.md", index = 0 };
+    include_file(tokens, collect).expect_err("expected 1-based error");
+}
+
+#[test]
+fn parse_index_str_err() {
+    let tokens = quote! { "README.md", index = "1" };
+    include_file(tokens, collect).expect_err("expected integer error");
+}
+
+#[test]
+fn parse_lines_param() {
+    let tokens = quote! { "README.md", lines = "1..2" };
+    include_file(tokens.clone(), collect).expect("expected TokenStream");
+
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.selector, Selector::Lines(1..2));
+}
+
+#[test]
+fn parse_name_and_index_err() {
+    let tokens = quote! { "README.md", "example", index = 1 };
+    include_file(tokens, collect).expect_err("expected conflicting selector error");
+}
+
+#[test]
+fn parse_index_and_lines_err() {
+    let tokens = quote! { "README.md", index = 1, lines = "1..2" };
+    include_file(tokens, collect).expect_err("expected conflicting selector error");
+}
+
+#[test]
+fn parse_no_selector_err() {
+    let tokens = quote! { "README.md", scope };
+    include_file(tokens, collect).expect_err("expected missing parameters error");
+}
+
+#[test]
+fn parse_items_param() {
+    let tokens = quote! { "README.md", "example", items };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.mode, Mode::Items);
+    assert!(args.scope.is_none());
+}
+
+#[test]
+fn parse_items_with_scope_err() {
+    let tokens = quote! { "README.md", "example", items, scope };
+    include_file(tokens, collect).expect_err("expected items in scope error");
+}
+
+#[test]
+fn parse_items_twice_err() {
+    let tokens = quote! { "README.md", "example", items, items };
+    include_file(tokens, collect).expect_err("expected only one mode error");
+}
+
+#[test]
+fn include_file_items() {
+    let tokens = quote! { "README.md", "example", items };
+    let actual = include_file(tokens, collect).expect("expected include_file");
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("README.md")
+        .canonicalize()
+        .expect("expected canonical path");
+    let path = path.to_string_lossy();
+    assert_eq!(
+        actual.to_string(),
+        quote! {
+            const _: ::std::option::Option<&str> = ::std::option_env!("INCLUDE_FILE_MANIFEST");
+            const _: &[u8] = ::std::include_bytes!(#path);
+            println!("example");
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn parse_expr_param() {
+    let tokens = quote! { "README.md", "example", expr };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.mode, Mode::Expr);
+}
+
+#[test]
+fn parse_expr_and_items_err() {
+    let tokens = quote! { "README.md", "example", expr, items };
+    include_file(tokens, collect).expect_err("expected only one mode error");
+}
+
+#[test]
+fn include_file_expr() {
+    let tokens = quote! { "README.md", "example", expr };
+    let actual = include_file(tokens, collect).expect("expected include_file");
+    let mut iter = actual.clone().into_iter();
+    assert!(matches!(
+        iter.next(),
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace,
+    ));
+    assert!(iter.next().is_none());
+    assert!(actual.to_string().contains("__include_file_value"));
+}
+
+#[test]
+fn parse_async_param() {
+    let tokens = quote! { "README.md", "example", async };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.runtime, Some(Runtime::Tokio));
+}
+
+#[test]
+fn parse_runtime_param() {
+    let tokens = quote! { "README.md", "example", async, runtime = "async_std" };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.runtime, Some(Runtime::AsyncStd));
+
+    let tokens = quote! { "README.md", "example", runtime = "crate::block_on" };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(
+        args.runtime,
+        Some(Runtime::Custom("crate :: block_on".into()))
+    );
+}
+
+#[test]
+fn parse_runtime_invalid_err() {
+    let tokens = quote! { "README.md", "example", async, runtime = "not a path" };
+    include_file(tokens, collect).expect_err("expected invalid runtime error");
+}
+
+#[test]
+fn parse_async_items_err() {
+    let tokens = quote! { "README.md", "example", items, async };
+    include_file(tokens, collect).expect_err("expected items in async error");
+}
+
+#[test]
+fn parse_root_param() {
+    let tokens = quote! { "README.md", "example", root = "workspace" };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.root, Root::Workspace);
+
+    let tokens = quote! { "README.md", "example", root = "out_dir" };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.root, Root::OutDir);
+
+    let tokens = quote! { "README.md", "example", root = env!("DOCS_DIR") };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.root, Root::Env("DOCS_DIR".into()));
+}
+
+#[test]
+fn include_file_env_root() {
+    let tokens = quote! { "README.md", "example", root = env!("CARGO_MANIFEST_DIR") };
+    let actual = include_file(tokens, collect).expect("expected include_file");
+    let expected =
+        quote! { const _: ::std::option::Option<&str> = ::std::option_env!("CARGO_MANIFEST_DIR"); };
+    assert!(
+        actual.to_string().contains(&expected.to_string()),
+        "{actual}"
+    );
+}
+
+#[test]
+fn parse_root_invalid_err() {
+    let tokens = quote! { "README.md", "example", root = "docs" };
+    include_file(tokens, collect).expect_err("expected invalid root error");
+
+    let tokens = quote! { "README.md", "example", root = concat!("docs") };
+    include_file(tokens, collect).expect_err("expected invalid root error");
+}
+
+#[test]
+fn parse_root_relative_err() {
+    let tokens = quote! { "README.md", "example", root = "workspace", relative };
+    include_file(tokens, collect).expect_err("expected root with relative error");
+}
+
+#[test]
+fn include_file_glob() {
+    let tokens = quote! { "tests/*.adoc", "example" };
+    include_file(tokens, collect).expect("expected include_file");
+}
+
+#[test]
+fn include_file_glob_ambiguous_err() {
+    let tokens = quote! { "tests/README.*", "example" };
+    let err = include_file(tokens, collect).expect_err("expected ambiguous error");
+    let message = err.to_string();
+    assert!(message.starts_with("code fence 'example' is ambiguous; found in "));
+    assert!(message.contains("README.adoc:1"), "{message}");
+    assert!(message.contains("README.org:1"), "{message}");
+}
+
+#[test]
+fn include_file_glob_no_match_err() {
+    let tokens = quote! { "tests/*.missing", "example" };
+    let err = include_file(tokens, collect).expect_err("expected no match error");
+    assert_eq!(err.to_string(), "no files match tests/*.missing");
+}
+
+#[test]
+fn parse_glob_index_err() {
+    let tokens = quote! { "tests/*.adoc", index = 1 };
+    include_file(tokens, collect).expect_err("expected glob requires name error");
+}
+
+#[test]
+fn include_file_replace() {
+    let tokens = quote! {
+        "README.md",
+        "example",
+        items,
+        replace = [("example", "replaced"), (regex(r"print(ln)?!"), "eprint${1}!")],
+    };
+    let actual = include_file(tokens, collect).expect("expected include_file");
+    let expected = quote! { eprintln!("replaced"); }.to_string();
+    assert!(actual.to_string().ends_with(&expected), "{actual}");
+}
+
+#[test]
+fn include_file_replace_no_match_err() {
+    let tokens = quote! { "README.md", "example", replace = [("example", "x"), ("missing", "x")] };
+    let err = include_file(tokens, collect).expect_err("expected no match error");
+    assert_eq!(
+        err.to_string(),
+        r#"replacement pattern "missing" matched nothing"#
+    );
+
+    let tokens = quote! { "README.md", "example", replace = [(regex("^$"), "x")] };
+    let err = include_file(tokens, collect).expect_err("expected no match error");
+    assert_eq!(
+        err.to_string(),
+        r#"replacement pattern regex("^$") matched nothing"#
+    );
+}
+
+fn collect_unterminated<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    Ok(vec![
+        Block {
+            names: vec!["other".into()],
+            start_line: 2,
+            lines: vec!["let x = 1;".into()],
+            ..Default::default()
+        },
+        Block {
+            names: vec!["example".into()],
+            open_line: 12,
+            start_line: 13,
+            lines: vec!["let y = 2;".into(), "```".into(), "More.".into()],
+            unterminated: true,
+            ..Default::default()
+        },
+    ])
+}
+
+#[test]
+fn include_file_unterminated_err() {
+    let expected = "unterminated code fence 'example' opened at README.md:12";
+    for tokens in [
+        quote! { "README.md", "example" },
+        quote! { "README.md", "missing" },
+    ] {
+        let err = include_file(tokens, collect_unterminated).expect_err("expected unterminated");
+        assert_eq!(err.to_string(), expected);
+    }
+
+    // Blocks before an unterminated block are complete.
+    let tokens = quote! { "README.md", "other" };
+    include_file(tokens, collect_unterminated).expect("expected block before unterminated");
+}
+
+fn collect_statements<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    Ok(vec![Block {
+        names: vec!["example".into()],
+        start_line: 40,
+        lines: vec![
+            "// Setup.".into(),
+            "let a = 1;".into(),
+            "".into(),
+            "assert_eq!(a, 2);".into(),
+        ],
+        ..Default::default()
+    }])
+}
+
+#[test]
+fn include_file_statement_lines() {
+    let tokens = quote! { "README.md", "example" };
+    let actual = include_file(tokens, collect_statements)
+        .expect("expected TokenStream")
+        .to_string();
+    assert!(actual.contains(r#", "40-43" ,"#), "{actual}");
+    assert!(actual.contains("Cell :: new (41u32)"), "{actual}");
+    assert!(
+        actual.contains("let a = 1 ; __include_file_guard"),
+        "{actual}"
+    );
+    assert!(
+        actual.contains(". line . set (43u32) ; assert_eq !"),
+        "{actual}"
+    );
+}
+
+#[test]
+fn include_file_statement_lines_replaced() {
+    // Lines are those of the block as written even when a replacement removes a line.
+    let tokens = quote! { "README.md", "example", replace = [("// Setup.\n", "")] };
+    let actual = include_file(tokens, collect_statements)
+        .expect("expected TokenStream")
+        .to_string();
+    assert!(actual.contains(r#", "40-43" ,"#), "{actual}");
+    assert!(
+        actual.contains(". line . set (43u32) ; assert_eq !"),
+        "{actual}"
+    );
+    assert!(actual.contains("(statement at line {})"), "{actual}");
+
+    // Statements cannot be marked when a replacement adds one, so none is reported.
+    let tokens =
+        quote! { "README.md", "example", replace = [("let a = 1;", "let a = 1; let b = a;")] };
+    let actual = include_file(tokens, collect_statements)
+        .expect("expected TokenStream")
+        .to_string();
+    assert!(!actual.contains(". line . set"), "{actual}");
+    assert!(!actual.contains("statement at line"), "{actual}");
+    assert!(
+        actual.contains(r#""panicked in code included from {}:{}""#),
+        "{actual}"
+    );
+}
+
+#[test]
+fn include_file_statement_lines_unmarked() {
+    for tokens in [
+        quote! { "README.md", "example", doctest },
+        quote! { "README.md", "example", duplicates = "all" },
+    ] {
+        let actual = include_file(tokens, collect_statements)
+            .expect("expected TokenStream")
+            .to_string();
+        assert!(!actual.contains("statement at line"), "{actual}");
+    }
+}
+
+fn collect_duplicates<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    Ok(vec![
+        Block {
+            names: vec!["example".into()],
+            start_line: 3,
+            lines: vec!["let first = 1;".into()],
+            ..Default::default()
+        },
+        Block {
+            names: vec!["example".into()],
+            start_line: 10,
+            lines: vec!["let last = first;".into()],
+            ..Default::default()
+        },
+    ])
+}
+
+#[test]
+fn include_file_duplicates_err() {
+    let tokens = quote! { "README.md", "example" };
+    let err = include_file(tokens, collect_duplicates).expect_err("expected ambiguous name");
+    assert_eq!(
+        err.to_string(),
+        "code fence 'example' is ambiguous; found in README.md:3, README.md:10"
+    );
+
+    let tokens = quote! { "README.md", "example", duplicates = "any" };
+    include_file(tokens, collect_duplicates).expect_err("expected invalid policy");
+
+    for tokens in [
+        quote! { "README.md", index = 1, duplicates = "first" },
+        quote! { "README.md", lines = "3..4", duplicates = "all" },
+    ] {
+        let err =
+            include_file(tokens, collect_duplicates).expect_err("expected rejected duplicates");
+        assert!(
+            err.to_string()
+                .ends_with(r#"selects one code fence; "duplicates" requires a "name""#),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn include_file_duplicates() {
+    for (policy, expected) in [
+        ("first", quote! { let first = 1; }),
+        ("last", quote! { let last = first; }),
+        ("all", quote! { let first = 1; let last = first; }),
+    ] {
+        let tokens = quote! { "README.md", "example", duplicates = #policy };
+        let actual = include_file(tokens, collect_duplicates).expect("expected TokenStream");
+        assert!(
+            actual.to_string().contains(&expected.to_string()),
+            "{policy}: {actual}"
+        );
+    }
+}
+
+#[test]
+fn include_file_sha256() {
+    let tokens = quote! { "README.md", "example", sha256 = "8c880173004221f98f772336f981f3b1b2a47859c3c789b34252f4275d0009fc" };
+    include_file(tokens, collect).expect("expected matching hash");
+
+    let tokens = quote! { "README.md", "example", sha256 = "0000000000000000000000000000000000000000000000000000000000000000" };
+    let err = include_file(tokens, collect).expect_err("expected changed hash");
+    assert_eq!(
+        err.to_string(),
+        r#"code fence 'example' changed; review it and update to sha256 = "8c880173004221f98f772336f981f3b1b2a47859c3c789b34252f4275d0009fc""#
+    );
+
+    let tokens = quote! { "README.md", "example", sha256 = "8c880173" };
+    include_file(tokens, collect).expect_err("expected invalid hash");
+}
+
+#[test]
+fn parse_replace_invalid_err() {
+    for tokens in [
+        quote! { "README.md", "example", replace = [] },
+        quote! { "README.md", "example", replace = ("a", "b") },
+        quote! { "README.md", "example", replace = [("a")] },
+        quote! { "README.md", "example", replace = [("", "b")] },
+        quote! { "README.md", "example", replace = [(glob("a"), "b")] },
+        quote! { "README.md", "example", replace = [(regex("("), "b")] },
+    ] {
+        include_file(tokens, collect).expect_err("expected invalid replace error");
+    }
+}
+
+fn collect_placeholders<R: io::Read>(_iter: io::Lines<io::BufReader<R>>) -> io::Result<Vec<Block>> {
+    Ok(vec![Block {
+        names: vec!["example".into()],
+        start_line: 10,
+        lines: vec![
+            r#"let name = "{{crate_name}}";"#.into(),
+            r#"let home = "{{env:CARGO_MANIFEST_DIR}}";"#.into(),
+            r#"let other = "{{name}}";"#.into(),
+        ],
+        ..Default::default()
+    }])
+}
+
+#[test]
+fn include_file_substitute() {
+    let tokens = quote! { "README.md", lines = "10..12", items, substitute };
+    let actual = include_file(tokens, collect_placeholders).expect("expected include_file");
+    let home = env!("CARGO_MANIFEST_DIR");
+    let expected = quote! {
+        const _: ::std::option::Option<&str> = ::std::option_env!("CARGO_PKG_NAME");
+        const _: ::std::option::Option<&str> = ::std::option_env!("CARGO_MANIFEST_DIR");
+        let name = "include_file";
+        let home = #home;
+    }
+    .to_string();
+    assert!(actual.to_string().ends_with(&expected), "{actual}");
+}
+
+#[test]
+fn include_file_substitute_unknown_err() {
+    let tokens = quote! { "README.md", "example", substitute };
+    let err = include_file(tokens, collect_placeholders).expect_err("expected unknown placeholder");
+    assert_eq!(
+        err.to_string(),
+        "unknown placeholder {{name}} at README.md:12"
+    );
+
+    // The line is counted before replacements join lines.
+    let tokens = quote! { "README.md", "example", substitute, replace = [(regex(r"\n"), " ")] };
+    let err = include_file(tokens, collect_placeholders).expect_err("expected unknown placeholder");
+    assert_eq!(
+        err.to_string(),
+        "unknown placeholder {{name}} at README.md:12"
+    );
+
+    // Placeholders are left as-is without `substitute`.
+    let tokens = quote! { "README.md", "example", expr };
+    include_file(tokens, collect_placeholders).expect("expected include_file");
+}
+
+fn parse_with(tokens: TokenStream, config: &Config) -> syn::Result<MarkdownArgs> {
+    (|input: ParseStream| MarkdownArgs::parse_with(input, config)).parse2(tokens)
+}
+
+#[test]
+fn parse_config_defaults() {
+    let config = Config {
+        root: Root::Workspace,
+        mode: Mode::Expr,
+        scope: true,
+        ..Default::default()
+    };
+    let args = parse_with(quote! { "README.md", "example" }, &config).expect("expected args");
+    assert_eq!(args.root, Root::Workspace);
+    assert_eq!(args.mode, Mode::Expr);
+    assert!(args.scope.is_some());
+}
+
+#[test]
+fn parse_config_overrides() {
+    let config = Config {
+        root: Root::Workspace,
+        mode: Mode::Expr,
+        scope: true,
+        ..Default::default()
+    };
+    let tokens = quote! { "README.md", "example", root = "package", doctest, scope = false };
+    let args = parse_with(tokens, &config).expect("expected args");
+    assert_eq!(args.root, Root::Manifest);
+    assert_eq!(args.mode, Mode::Doctest);
+    assert!(args.scope.is_none());
+
+    // `statements` overrides the default mode.
+    let tokens = quote! { "README.md", "example", statements };
+    let args = parse_with(tokens, &config).expect("expected args");
+    assert_eq!(args.mode, Mode::Statements);
+
+    // `relative` overrides the default root, and the default scope does not apply to items.
+    let tokens = quote! { "README.md", "example", relative, items };
+    let args = parse_with(tokens, &config).expect("expected args");
+    assert_eq!(args.root, Root::Manifest);
+    assert!(args.relative.is_some());
+    assert!(args.scope.is_none());
+}
+
+#[test]
+fn include_file_async() {
+    let tokens = quote! { "README.md", "example", async, runtime = "block_on" };
+    let actual = include_file(tokens, collect)
+        .expect("expected include_file")
+        .to_string();
+    assert!(actual.contains(&quote! { block_on(async { println!("example"); }); }.to_string()));
+}
+
+#[test]
+fn uses_try_operator() {
+    assert!(uses_try(quote! { let m = example()?; }));
+    assert!(uses_try(quote! { if true { example()?; } }));
+    assert!(!uses_try(quote! { let m = example(); }));
+    assert!(!uses_try(quote! { let m = async { example()? }.await; }));
+    assert!(!uses_try(
+        quote! { let m = async move { example()? }.await; }
+    ));
+}
+
+#[test]
+fn parse_doctest_param() {
+    let tokens = quote! { "README.md", "example", doctest, scope };
+    let args: MarkdownArgs = parse2(tokens).expect("expected parse2");
+    assert_eq!(args.mode, Mode::Doctest);
+    assert!(args.scope.is_some());
+}
+
+#[test]
+fn parse_doctest_async_err() {
+    let tokens = quote! { "README.md", "example", doctest, async };
+    include_file(tokens, collect).expect_err("expected doctest in async error");
+}
+
+#[test]
+fn doctest_statements() {
+    let actual = doctest(quote! { let x = 1; }, 0, None);
+    assert_eq!(
+        actual.to_string(),
+        quote! {
+            fn __include_file_doctest0() {
+                let x = 1;
+            }
+            __include_file_doctest0();
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn doctest_inner_attributes() {
+    let actual = doctest(quote! { #![allow(unused)] let x = 1; }, 0, None);
+    assert_eq!(
+        actual.to_string(),
+        quote! {
+            fn __include_file_doctest0() {
+                #![allow(unused)]
+                let x = 1;
+            }
+            __include_file_doctest0();
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn doctest_main() {
+    let actual = doctest(quote! { fn main() {} }, 0, None).to_string();
+    assert!(actual.contains("Termination :: report (main ())"));
+}
+
+#[test]
+fn doctest_ends_with_unit() {
+    let actual = doctest(quote! { Ok::<(), String>(()) }, 0, None).to_string();
+    assert!(actual.contains("impl :: std :: fmt :: Debug"));
+}
+
+#[test]
+fn doctest_uses_try() {
+    let actual = doctest(quote! { let x = f()?; }, 0, None).to_string();
+    assert!(actual.contains("dyn :: std :: error :: Error"));
+}
+
+#[test]
+fn doctest_extern_crate() {
+    let actual = doctest(
+        quote! { #![allow(unused)] #[macro_use] extern crate serde; extern crate std as other; let x = 1; },
+        0,
+        None,
+    );
+    assert_eq!(
+        actual.to_string(),
+        quote! {
+            #[macro_use] extern crate serde;
+            extern crate std as other;
+            fn __include_file_doctest0() {
+                #![allow(unused)]
+                let x = 1;
+            }
+            __include_file_doctest0();
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn doctest_inject_crate() {
+    let actual = doctest(
+        quote! { let m = { example::Model::new() }; },
+        0,
+        Some("example"),
+    );
+    assert_eq!(
+        actual.to_string(),
+        quote! {
+            #[allow(unused_extern_crates)]
+            extern crate example;
+            fn __include_file_doctest0() {
+                let m = { example::Model::new() };
+            }
+            __include_file_doctest0();
+        }
+        .to_string()
+    );
+
+    // The library is not injected if not named or already declared.
+    let actual = doctest(quote! { let x = 1; }, 0, Some("example")).to_string();
+    assert!(!actual.contains("extern crate"), "{actual}");
+    let actual = doctest(
+        quote! { extern crate example; let m = example::Model::new(); },
+        0,
+        Some("example"),
+    )
+    .to_string();
+    assert_eq!(actual.matches("extern crate").count(), 1, "{actual}");
+}
+
 #[test]
 fn include_file_scope() {
     let tokens = quote! { "README.md", "example", scope };
@@ -162,25 +858,87 @@ fn include_file_no_scope() {
 
 #[test]
 fn open_file() {
-    let (file, _) = open(None, "README.md").expect("expected README.md");
+    let OpenFile { file, .. } =
+        open(&Config::default(), &Root::Manifest, "README.md").expect("expected README.md");
     assert!(matches!(file.metadata(), Ok(meta) if meta.is_file()));
 }
 
 #[test]
 fn open_relative_file() {
-    let (file, _) = open(Some(file!().into()), "../README.md").expect("expected README.md");
+    let OpenFile { file, .. } = open(
+        &Config::default(),
+        &Root::Source(file!().into()),
+        "../README.md",
+    )
+    .expect("expected README.md");
     assert!(matches!(file.metadata(), Ok(meta) if meta.is_file()));
 }
 
 #[test]
 fn open_err() {
-    assert!(matches!(open(None, "missing.txt"), Err(err) if err.kind() == io::ErrorKind::NotFound));
+    assert!(
+        matches!(open(&Config::default(), &Root::Manifest, "missing.txt"), Err(err) if err.kind() == io::ErrorKind::NotFound)
+    );
+}
+
+#[test]
+fn open_env_root() {
+    let OpenFile { display_path, .. } = open(
+        &Config::default(),
+        &Root::Env("CARGO_MANIFEST_DIR".into()),
+        "README.md",
+    )
+    .expect("expected README.md");
+    let expected = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("README.md");
+    assert_eq!(
+        PathBuf::from(display_path),
+        expected.canonicalize().expect("expected canonical path")
+    );
+}
+
+#[test]
+fn open_out_dir_err() {
+    // Cargo sets OUT_DIR when compiling but not when running tests.
+    if env::var_os("OUT_DIR").is_none() {
+        assert!(matches!(
+            open(&Config::default(), &Root::OutDir, "README.md"),
+            Err(err) if err.kind() == io::ErrorKind::NotFound
+        ));
+    }
+}
+
+#[cfg(feature = "strict")]
+#[test]
+fn open_strict_outside_err() {
+    let path = env::temp_dir().join(format!("include-file-strict-{}.md", std::process::id()));
+    std::fs::write(&path, "outside").expect("write temp file");
+    let result = open(&Config::default(), &Root::Manifest, &path.to_string_lossy());
+    std::fs::remove_file(&path).ok();
+    assert!(matches!(result, Err(err) if err.kind() == io::ErrorKind::PermissionDenied));
+}
+
+#[test]
+fn workspace_dir_member() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    assert_eq!(workspace_dir(&root).expect("expected workspace"), root);
+    assert_eq!(
+        workspace_dir(&root.join("tests").join("member")).expect("expected workspace"),
+        root
+    );
+    // Excluded packages are their own workspace root.
+    let non_member = root.join("tests").join("non-member");
+    assert_eq!(
+        workspace_dir(&non_member).expect("expected workspace"),
+        non_member
+    );
 }
 
 #[test]
 fn display_path_without_relative() {
     // Without `relative`, the user-supplied path is already relative to CARGO_MANIFEST_DIR.
-    let (_, display_path) = open(None, "tests/README.adoc").expect("expected tests/README.adoc");
+    let OpenFile { display_path, .. } =
+        open(&Config::default(), &Root::Manifest, "tests/README.adoc")
+            .expect("expected tests/README.adoc");
     if cfg!(windows) {
         assert_eq!(display_path, "tests\\README.adoc");
     } else {
@@ -194,7 +952,8 @@ fn display_path_with_relative() {
     // Simulates include_asciidoc!("README.adoc", "example", relative) called from
     // tests/readme.rs: the note should still report "tests/README.adoc".
     let src = PathBuf::from("tests/readme.rs");
-    let (_, display_path) = open(Some(src), "README.adoc").expect("expected tests/README.adoc");
+    let OpenFile { display_path, .. } = open(&Config::default(), &Root::Source(src), "README.adoc")
+        .expect("expected tests/README.adoc");
     if cfg!(windows) {
         assert_eq!(display_path, "tests\\README.adoc");
     } else {
@@ -0,0 +1,16 @@
+// Setup code hidden in each named Rust code fence in README.md.
+#[derive(Debug)]
+struct Model {
+    name: String,
+}
+
+fn example() -> Result<Model, Box<dyn std::error::Error>> {
+    Ok(Model {
+        name: "example".into(),
+    })
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // snippet
+    Ok(())
+}
@@ -0,0 +1,8 @@
+// Copyright 2026 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+//! Tests for a crate within the workspace.
+
+/// Code fences run by `cargo test --doc` with setup code hidden by `include_markdown_doctests!`.
+#[doc = include_file::include_markdown_doctests!("doctests/README.md", prelude = "doctests/README.rs")]
+pub mod doctests {}
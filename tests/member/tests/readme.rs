@@ -1,7 +1,7 @@
 // Copyright 2026 Heath Stewart.
 // Licensed under the MIT License. See LICENSE.txt in the project root for license information.
 
-use include_file::include_markdown;
+use include_file::{include_asciidoc, include_markdown};
 
 #[test]
 fn test_example() -> Result<(), Box<dyn std::error::Error>> {
@@ -9,6 +9,12 @@ fn test_example() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_workspace_root() -> Result<(), Box<dyn std::error::Error>> {
+    include_markdown!("README.md", "example", root = "workspace");
+    Ok(())
+}
+
 #[test]
 fn test_panic_reports_workspace_path() {
     let exe = std::env::current_exe().unwrap();
@@ -32,6 +38,93 @@ fn test_panic_reports_workspace_path() {
     );
 }
 
+#[test]
+fn test_panic_reports_workspace_root_path() {
+    let exe = std::env::current_exe().unwrap();
+    let output = std::process::Command::new(&exe)
+        .arg("--exact")
+        .arg("panic_in_workspace_readme")
+        .arg("--nocapture")
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let expected = if cfg!(windows) {
+        "tests\\lines\\README.adoc:13"
+    } else {
+        "tests/lines/README.adoc:13"
+    };
+    assert!(
+        stderr.contains(expected),
+        "expected '{expected}' in stderr:\n{stderr}"
+    );
+}
+
+/// Runs the test named `test` and returns the JSON note it prints when it panics.
+fn panic_note_json(test: &str) -> serde_json::Value {
+    let exe = std::env::current_exe().unwrap();
+    let output = std::process::Command::new(&exe)
+        .arg("--exact")
+        .arg(test)
+        .arg("--nocapture")
+        .env("RUST_BACKTRACE", "0")
+        .env("INCLUDE_FILE_NOTE_FORMAT", "json")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stderr
+        .lines()
+        .find(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("expected JSON note"))
+        .unwrap_or_else(|| panic!("expected JSON note in stderr:\n{stderr}"))
+}
+
+// Verify that the JSON note is valid JSON, including backslashes in Windows paths.
+#[test]
+fn test_panic_note_json() {
+    let note = panic_note_json("panic_in_workspace_readme");
+    let expected = if cfg!(windows) {
+        "tests\\lines\\README.adoc"
+    } else {
+        "tests/lines/README.adoc"
+    };
+    assert_eq!(note["file"], expected);
+    assert_eq!(note["start_line"], 13);
+    assert_eq!(note["end_line"], 13);
+    assert_eq!(note["line"], 13);
+    assert_eq!(
+        note["message"],
+        format!("panicked in code included from {expected}:13 (statement at line 13)")
+    );
+}
+
+// Verify that the line is null if statements are not marked, as within a doctest.
+#[test]
+fn test_panic_note_json_unmarked() {
+    let note = panic_note_json("panic_in_doctest");
+    assert!(note["line"].is_null(), "{note}");
+    assert_eq!(note["start_line"], 13);
+}
+
+#[test]
+#[should_panic(expected = "intentional assert failure")]
+fn panic_in_workspace_readme() {
+    include_asciidoc!("tests/lines/README.adoc", "assert-fail", root = "workspace");
+}
+
+#[test]
+#[should_panic(expected = "intentional assert failure")]
+fn panic_in_doctest() {
+    include_asciidoc!(
+        "tests/lines/README.adoc",
+        "assert-fail",
+        root = "workspace",
+        doctest
+    );
+}
+
 #[test]
 #[should_panic(expected = "intentional assert failure")]
 fn panic_in_readme() {
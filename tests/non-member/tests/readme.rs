@@ -38,6 +38,37 @@ fn test_panic_reports_crate_relative_path() {
     );
 }
 
+// Verify that test reporters can opt into GitHub annotations or JSON lines.
+#[test]
+fn test_panic_note_formats() {
+    let exe = std::env::current_exe().unwrap();
+    for (format, expected) in [
+        (
+            "github",
+            "::error file=README.md,line=9,title=include-file::panicked in code included from README.md:9 (statement at line 9)",
+        ),
+        (
+            "json",
+            r#"{"file":"README.md","start_line":9,"end_line":9,"line":9,"message":"panicked in code included from README.md:9 (statement at line 9)"}"#,
+        ),
+    ] {
+        let output = std::process::Command::new(&exe)
+            .arg("--exact")
+            .arg("panic_in_readme")
+            .arg("--nocapture")
+            .env("RUST_BACKTRACE", "0")
+            .env("INCLUDE_FILE_NOTE_FORMAT", format)
+            .output()
+            .unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.lines().any(|line| line == expected),
+            "expected {format} note in stderr:\n{stderr}"
+        );
+    }
+}
+
 #[test]
 #[should_panic(expected = "intentional assert failure")]
 fn panic_in_readme() {
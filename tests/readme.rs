@@ -1,13 +1,15 @@
 // Copyright 2025 Heath Stewart.
 // Licensed under the MIT License. See LICENSE.txt in the project root for license information.
 
-#[cfg(feature = "asciidoc")]
-use include_file::include_asciidoc;
-use include_file::include_markdown;
 #[cfg(feature = "org")]
 use include_file::include_org;
 #[cfg(feature = "textile")]
 use include_file::include_textile;
+#[cfg(feature = "asciidoc")]
+use include_file::{assert_all_included, include_asciidoc};
+use include_file::{
+    include_markdown, include_markdown_data, include_markdown_doctests, include_markdown_section,
+};
 
 #[cfg(feature = "asciidoc")]
 #[test]
@@ -22,6 +24,120 @@ fn test_markdown() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_markdown_index() -> Result<(), Box<dyn std::error::Error>> {
+    include_markdown!("README.md", index = 1, scope);
+    Ok(())
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_lines() {
+    include_asciidoc!("tests/lines/README.adoc", lines = "7..9");
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_glob() {
+    include_asciidoc!("tests/*.adoc", "simple-assert");
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_substitute() {
+    include_asciidoc!("tests/README.adoc", "substitute", substitute);
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_replace() {
+    include_asciidoc!(
+        "tests/README.adoc",
+        "replace",
+        replace = [("https://api.example.com", "http://127.0.0.1:8080")]
+    );
+}
+
+#[test]
+fn test_markdown_data() -> Result<(), Box<dyn std::error::Error>> {
+    let config: toml::Table =
+        include_markdown_data!("README.md", "config", format = "toml").parse()?;
+    assert_eq!(
+        config["package"]["metadata"]["include-file"]["root"].as_str(),
+        Some("workspace")
+    );
+    Ok(())
+}
+
+// Verify that sections expand to a literal usable in attributes.
+#[doc = include_markdown_section!("README.md", "License", demote = 1)]
+#[test]
+fn test_markdown_section() {
+    let section = include_markdown_section!("README.md", "License");
+    assert!(section.starts_with("## License\n"), "{section}");
+}
+
+// Verify that setup code is hidden in named code fences for doctests.
+#[test]
+fn test_markdown_doctests() {
+    let doc = include_markdown_doctests!(
+        "tests/member/doctests/README.md",
+        prelude = "tests/member/doctests/README.rs"
+    );
+    assert!(
+        doc.contains("```rust example\n# // Setup code hidden"),
+        "{doc}"
+    );
+    assert!(
+        doc.contains(
+            "# fn main() -> Result<(), Box<dyn std::error::Error>> {\nlet m = example()?;"
+        ),
+        "{doc}"
+    );
+    assert!(doc.contains("# let mut m = example()?;\nm.name"), "{doc}");
+    assert!(!doc.contains("```rust prelude"), "{doc}");
+}
+
+// Verify that other formats are converted to Markdown usable in attributes.
+#[cfg(feature = "org")]
+#[doc = include_file::include_doc_as_markdown!("tests/README.org")]
+#[test]
+fn test_org_as_markdown() {
+    let section = include_file::include_doc_as_markdown!("tests/README.org", "License");
+    assert!(section.starts_with("## License\n"), "{section}");
+}
+
+// Verify that snippets are wrapped like rustdoc does for doctests.
+#[test]
+fn test_markdown_doctest() {
+    include_markdown!("README.md", "example", doctest);
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_doctest_main() {
+    include_asciidoc!("tests/README.adoc", "doctest-main", doctest);
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_doctest_result() {
+    include_asciidoc!("tests/README.adoc", "doctest-result", doctest);
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_doctest_extern() {
+    include_asciidoc!("tests/README.adoc", "doctest-extern", doctest);
+}
+
+// Verify that every Rust code block is included by a test or opted out.
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_all_included() {
+    assert_all_included!("tests/README.adoc");
+}
+
 // Verify that two includes in the same function generate unique guard names.
 #[test]
 fn test_multiple_includes() -> Result<(), Box<dyn std::error::Error>> {
@@ -45,6 +161,45 @@ fn test_only_one_note_on_panic() {
     include_asciidoc!("tests/README.adoc", "assert-fail");
 }
 
+// Verify that items are included at module scope without a guard,
+// and that expressions can use them.
+#[cfg(feature = "asciidoc")]
+mod items {
+    use include_file::include_asciidoc;
+
+    include_asciidoc!("tests/README.adoc", "items", items);
+
+    #[test]
+    fn test_asciidoc_items() {
+        assert_eq!(Point::origin(), Point { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn test_asciidoc_expr() {
+        let sum: i32 = include_asciidoc!("tests/README.adoc", "expr", expr);
+        assert_eq!(sum, 0);
+    }
+
+    #[test]
+    fn test_asciidoc_async_expr() {
+        let sum: i32 = include_asciidoc!(
+            "tests/README.adoc",
+            "expr",
+            expr,
+            async,
+            runtime = "super::block_on"
+        );
+        assert_eq!(sum, 0);
+    }
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+fn test_asciidoc_async() -> Result<(), Box<dyn std::error::Error>> {
+    include_asciidoc!("tests/README.adoc", "async", async, runtime = "block_on");
+    Ok(())
+}
+
 #[cfg_attr(not(span_locations), ignore = "not supported")]
 #[test]
 fn test_relative_markdown() -> Result<(), Box<dyn std::error::Error>> {
@@ -86,3 +241,15 @@ fn example() -> Result<Model, Box<dyn std::error::Error>> {
         name: "example".into(),
     })
 }
+
+// A minimal executor for testing `async` snippets without depending on a runtime.
+#[allow(dead_code)]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}